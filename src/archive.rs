@@ -0,0 +1,115 @@
+use crate::{attachment::AttachmentKind, errors::ParseError};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Bytes every zip archive starts with.
+const ZIP_MAGIC: &[u8; 4] = b"PK\x03\x04";
+
+/// Name of the chat inside iOS exports. Android names it after the chat,
+/// e.g. `WhatsApp Chat with Alice.txt`.
+const IOS_CHAT_NAME: &str = "_chat.txt";
+
+/// A media file bundled with an exported chat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaFile {
+    pub name: String,
+    /// Uncompressed size in bytes.
+    pub size: u64,
+    pub kind: AttachmentKind,
+}
+
+/// The media files bundled with an exported chat, looked up by file name.
+#[derive(Debug, Default)]
+pub struct MediaIndex {
+    files: HashMap<String, MediaFile>,
+}
+
+impl MediaIndex {
+    /// Returns the bundled file with the given name, as written in the chat.
+    pub fn get(&self, name: &str) -> Option<&MediaFile> {
+        self.files.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &MediaFile> {
+        self.files.values()
+    }
+
+    /// Adds the files of another index, e.g. of an overlapping export.
+    pub fn extend(&mut self, other: MediaIndex) {
+        self.files.extend(other.files);
+    }
+}
+
+/// Checks whether the file at `path` is a zip archive by its first bytes.
+pub fn is_zip(path: &Path) -> Result<bool, ParseError> {
+    let mut magic = [0u8; 4];
+    let mut file = File::open(path)?;
+    let read = file.read(&mut magic)?;
+    Ok(starts_with_zip_magic(&magic[..read]))
+}
+
+/// Checks whether `bytes`, read from the start of an input, begin a zip archive.
+pub fn starts_with_zip_magic(bytes: &[u8]) -> bool {
+    bytes.starts_with(ZIP_MAGIC)
+}
+
+/// Reads the chat file out of an exported `.zip` archive, undecoded.
+///
+/// Picks `_chat.txt` when present, otherwise the first `.txt` file at the
+/// root of the archive. Returns `Err(ParseError::NoChatInArchive)` if
+/// there is none.
+pub fn read_chat<R: Read + Seek>(reader: R) -> Result<Vec<u8>, ParseError> {
+    let mut archive = ZipArchive::new(reader)?;
+    let names: Vec<String> = archive
+        .file_names()
+        .filter_map(|name| name.ok().map(|name| name.into_owned()))
+        .collect();
+    let chat_name = names
+        .iter()
+        .find(|name| name.as_str() == IOS_CHAT_NAME)
+        .or_else(|| {
+            names
+                .iter()
+                .find(|name| !name.contains('/') && name.to_lowercase().ends_with(".txt"))
+        })
+        .ok_or(ParseError::NoChatInArchive)?;
+    let mut content = Vec::new();
+    archive.by_name(chat_name)?.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// Lists the media files bundled in an exported `.zip` archive, that is
+/// every file except the chat text itself.
+pub fn index_media<R: Read + Seek>(reader: R) -> Result<MediaIndex, ParseError> {
+    let mut archive = ZipArchive::new(reader)?;
+    let mut index = MediaIndex::default();
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)?;
+        if !entry.is_file() {
+            continue;
+        }
+        let full_name = entry.name()?;
+        let name = full_name.rsplit('/').next().unwrap_or_default().to_string();
+        if name.to_lowercase().ends_with(".txt") {
+            continue;
+        }
+        let file = MediaFile {
+            kind: AttachmentKind::from_file_name(&name),
+            size: entry.size(),
+            name: name.clone(),
+        };
+        index.files.insert(name, file);
+    }
+    Ok(index)
+}
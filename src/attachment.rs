@@ -0,0 +1,138 @@
+use std::fmt;
+
+/// The type of file shared in a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttachmentKind {
+    Image,
+    Video,
+    /// Audio files and voice notes.
+    Audio,
+    Sticker,
+    Gif,
+    Document,
+    /// Contact cards (`.vcf`).
+    Contact,
+    /// Android's `<Media omitted>`, which doesn't say what was shared.
+    Media,
+}
+
+impl AttachmentKind {
+    /// Guesses the kind of a file bundled with an export from its name, e.g.
+    /// `00000012-PHOTO-2022-01-01-12-00-00.jpg` or `PTT-20220101-WA0003.opus`.
+    pub fn from_file_name(name: &str) -> AttachmentKind {
+        let upper = name.to_uppercase();
+        let extension = upper.rsplit_once('.').map_or("", |(_, ext)| ext);
+        let has = |markers: &[&str]| markers.iter().any(|marker| upper.contains(marker));
+        if has(&["-STICKER-", "STK-"]) || extension == "WEBP" {
+            AttachmentKind::Sticker
+        } else if has(&["-GIF-"]) || extension == "GIF" {
+            AttachmentKind::Gif
+        } else if has(&["-PHOTO-", "IMG-"]) || matches!(extension, "JPG" | "JPEG" | "PNG" | "HEIC")
+        {
+            AttachmentKind::Image
+        } else if has(&["-VIDEO-", "VID-"]) || matches!(extension, "MP4" | "MOV" | "3GP") {
+            AttachmentKind::Video
+        } else if has(&["-AUDIO-", "PTT-", "AUD-"])
+            || matches!(extension, "OPUS" | "M4A" | "MP3" | "OGG" | "AAC")
+        {
+            AttachmentKind::Audio
+        } else if extension == "VCF" {
+            AttachmentKind::Contact
+        } else {
+            AttachmentKind::Document
+        }
+    }
+}
+
+impl fmt::Display for AttachmentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            AttachmentKind::Image => "image",
+            AttachmentKind::Video => "video",
+            AttachmentKind::Audio => "audio",
+            AttachmentKind::Sticker => "sticker",
+            AttachmentKind::Gif => "GIF",
+            AttachmentKind::Document => "document",
+            AttachmentKind::Contact => "contact",
+            AttachmentKind::Media => "media",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A file shared in a message, as far as the export tells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    /// Name of the bundled file, when the export was made with media.
+    pub file_name: Option<String>,
+}
+
+impl Attachment {
+    /// Recognizes the attachment placeholder a message text starts with, such
+    /// as `<Media omitted>`, `image omitted`, `<attached: file.jpg>` or
+    /// `file.jpg (file attached)`.
+    ///
+    /// Returns the attachment together with the caption that follows the
+    /// placeholder, or `None` if the text doesn't start with one.
+    pub fn parse(text: &str) -> Option<(Attachment, String)> {
+        let (first_line, caption) = text.split_once('\n').unwrap_or((text, ""));
+        let first_line = first_line.trim();
+        let (attachment, rest) = if let Some(rest) = first_line.strip_prefix("<attached: ") {
+            let (file_name, rest) = rest.split_once('>')?;
+            (Attachment::from_file_name(file_name.trim()), rest)
+        } else if let Some((file_name, rest)) = first_line.split_once(" (file attached)") {
+            (Attachment::from_file_name(file_name.trim()), rest)
+        } else if let Some(rest) = first_line.strip_prefix("<Media omitted>") {
+            let attachment = Attachment {
+                kind: AttachmentKind::Media,
+                file_name: None,
+            };
+            (attachment, rest)
+        } else {
+            (Attachment::from_omitted(first_line)?, "")
+        };
+        let caption = [rest.trim(), caption.trim()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some((attachment, caption))
+    }
+
+    fn from_file_name(file_name: &str) -> Attachment {
+        Attachment {
+            kind: AttachmentKind::from_file_name(file_name),
+            file_name: Some(file_name.to_string()),
+        }
+    }
+
+    /// Reads iOS placeholders like `image omitted` or
+    /// `Report.pdf • 3 pages document omitted`.
+    fn from_omitted(line: &str) -> Option<Attachment> {
+        let raw = line.strip_suffix(" omitted")?;
+        let described = raw.to_lowercase();
+        let kind = match described.as_str() {
+            "image" => AttachmentKind::Image,
+            "video" => AttachmentKind::Video,
+            "audio" => AttachmentKind::Audio,
+            "sticker" => AttachmentKind::Sticker,
+            "gif" => AttachmentKind::Gif,
+            "contact card" => AttachmentKind::Contact,
+            "document" => AttachmentKind::Document,
+            _ if described.ends_with(" document") => {
+                let name = &raw[..raw.len() - " document".len()];
+                let file_name = name.split(" • ").next().unwrap_or_default().trim();
+                return Some(Attachment {
+                    kind: AttachmentKind::Document,
+                    file_name: Some(file_name.to_string()),
+                });
+            }
+            _ => return None,
+        };
+        Some(Attachment {
+            kind,
+            file_name: None,
+        })
+    }
+}
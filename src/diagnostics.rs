@@ -0,0 +1,89 @@
+use crate::message::Message;
+use std::fmt;
+
+/// Why a line of the export was not used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// Text found before the first message header, with nothing to attach it to.
+    BeforeFirstMessage,
+    /// A line that starts like a header, but whose time or layout could not be read.
+    MalformedHeader,
+    /// A row or message of an imported chat whose timestamp could not be read.
+    InvalidTimestamp,
+    /// A CSV row without some of the columns a message is read from.
+    MissingFields,
+    /// A line that isn't valid UTF-8 or UTF-16 text.
+    InvalidEncoding,
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            RejectReason::BeforeFirstMessage => "text before the first message",
+            RejectReason::MalformedHeader => "malformed message header",
+            RejectReason::InvalidTimestamp => "unreadable timestamp",
+            RejectReason::MissingFields => "missing fields",
+            RejectReason::InvalidEncoding => "undecodable text",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// A line of the export that was skipped while parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedLine {
+    /// 1-based line number in the chat file, or entry number for formats
+    /// that aren't read line by line.
+    pub line_number: usize,
+    pub content: String,
+    pub reason: RejectReason,
+}
+
+/// What the parser made of each line of an export.
+#[derive(Debug, Default, Clone)]
+pub struct ParseReport {
+    /// Lines read, or entries for formats that aren't read line by line.
+    pub lines_read: usize,
+    pub blank_lines: usize,
+    /// Headers of messages sent by participants.
+    pub messages: usize,
+    /// Lines appended to the message above them.
+    pub continuations: usize,
+    /// Headers of group events written by WhatsApp.
+    pub system_events: usize,
+    /// Messages left without a timestamp, such as when no date in the export
+    /// tells whether the day or the month comes first.
+    pub undated: usize,
+    pub rejects: Vec<RejectedLine>,
+}
+
+impl ParseReport {
+    /// Builds the report of a source that isn't read line by line, counting
+    /// each of its entries as one line.
+    pub fn of_entries(messages: &[Message]) -> ParseReport {
+        let system_events = messages.iter().filter(|m| !m.is_user()).count();
+        ParseReport {
+            lines_read: messages.len(),
+            messages: messages.len() - system_events,
+            system_events,
+            ..Default::default()
+        }
+    }
+
+    /// Adds the entries a source skipped to its report, each of them counted
+    /// as one line read.
+    pub fn with_rejects(mut self, rejects: Vec<RejectedLine>) -> ParseReport {
+        self.lines_read += rejects.len();
+        self.rejects.extend(rejects);
+        self
+    }
+
+    /// Share of the non-blank lines that ended up in a message, from 0 to 1.
+    pub fn coverage(&self) -> f64 {
+        let lines = self.lines_read - self.blank_lines;
+        if lines == 0 {
+            return 1.0;
+        }
+        (lines - self.rejects.len()) as f64 / lines as f64
+    }
+}
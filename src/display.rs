@@ -1,10 +1,12 @@
+use crate::diagnostics::ParseReport;
+use crate::stats::{DistinctiveTerms, EditCounts};
 use std::collections::HashMap;
 
 /// Prints the contents of a `HashMap<String, i64>` sorted in descending order by value.
 /// This is typically used to display word counts or message counts per user.
 pub fn print_hashmap(stats: HashMap<String, i64>) {
     let mut sorted: Vec<_> = stats.into_iter().collect();
-    sorted.sort_by(|a, b| b.1.cmp(&a.1));
+    sorted.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
     for (owner, count) in sorted {
         println!("{}: {}", owner, count);
     }
@@ -22,3 +24,115 @@ pub fn pretty_print_top_speakers(top_speakers: &HashMap<String, String>) {
     }
 }
 
+/// Prints a `HashMap<String, i64>` of per-period counts sorted by key, so that
+/// `YYYY-MM-DD`, `YYYY-MM` and `YYYY` keys come out in chronological order.
+pub fn print_timeline(stats: HashMap<String, i64>) {
+    let mut sorted: Vec<_> = stats.into_iter().collect();
+    sorted.sort();
+    for (period, count) in sorted {
+        println!("{}: {}", period, count);
+    }
+}
+
+/// Prints a per-user breakdown from a `HashMap<String, HashMap<String, i64>>`.
+/// Users are sorted by their total in descending order, and so are the
+/// entries listed under each user.
+pub fn print_breakdown(stats: HashMap<String, HashMap<String, i64>>) {
+    let mut users: Vec<_> = stats
+        .into_iter()
+        .map(|(user, counts)| (user, counts.values().sum::<i64>(), counts))
+        .collect();
+    users.sort_by_key(|(_, total, _)| std::cmp::Reverse(*total));
+    for (user, total, counts) in users {
+        println!("{}: {}", user, total);
+        let mut sorted: Vec<_> = counts.into_iter().collect();
+        sorted.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        for (key, count) in sorted {
+            println!("  {}: {}", key, count);
+        }
+    }
+}
+
+/// Sorts counts in descending order, breaking ties alphabetically so that
+/// the top entries are the same on every run.
+fn sorted_by_count(stats: HashMap<String, i64>) -> Vec<(String, i64)> {
+    let mut sorted: Vec<_> = stats.into_iter().collect();
+    sorted.sort_by(|(a_key, a), (b_key, b)| b.cmp(a).then_with(|| a_key.cmp(b_key)));
+    sorted
+}
+
+/// Prints the `top` entries of a `HashMap<String, i64>` with the highest values.
+pub fn print_top(stats: HashMap<String, i64>, top: usize) {
+    for (key, count) in sorted_by_count(stats).into_iter().take(top) {
+        println!("{}: {}", key, count);
+    }
+}
+
+/// Prints a per-user breakdown like [`print_breakdown`], listing only the
+/// `top` entries with the highest values under each user.
+pub fn print_top_breakdown(stats: HashMap<String, HashMap<String, i64>>, top: usize) {
+    let mut users: Vec<_> = stats
+        .into_iter()
+        .map(|(user, counts)| (user, counts.values().sum::<i64>(), counts))
+        .collect();
+    users.sort_by_key(|(_, total, _)| std::cmp::Reverse(*total));
+    for (user, total, counts) in users {
+        println!("{}: {}", user, total);
+        for (key, count) in sorted_by_count(counts).into_iter().take(top) {
+            println!("  {}: {}", key, count);
+        }
+    }
+}
+
+/// Prints a `HashMap<String, f64>` of per-user averages sorted in descending
+/// order by value, with two decimals.
+pub fn print_averages(stats: HashMap<String, f64>) {
+    let mut sorted: Vec<_> = stats.into_iter().collect();
+    sorted.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    for (owner, average) in sorted {
+        println!("{}: {:.2}", owner, average);
+    }
+}
+
+/// Prints the deleted and edited message counts of each user, sorted by the
+/// number of deleted messages in descending order.
+pub fn print_edits(edits: HashMap<String, EditCounts>) {
+    let mut sorted: Vec<_> = edits.into_iter().collect();
+    sorted.sort_by_key(|(_, counts)| std::cmp::Reverse((counts.deleted, counts.edited)));
+    for (user, counts) in sorted {
+        println!(
+            "{}: {} deleted, {} edited",
+            user, counts.deleted, counts.edited
+        );
+    }
+}
+
+/// Prints a summary of how the lines of an export were parsed, followed by
+/// every skipped line with its line number and the reason it was skipped.
+pub fn print_parse_report(report: &ParseReport) {
+    println!("Lines read: {}", report.lines_read);
+    println!("Messages: {}", report.messages);
+    println!("Continuation lines: {}", report.continuations);
+    println!("System events: {}", report.system_events);
+    println!("Undated messages: {}", report.undated);
+    println!("Blank lines: {}", report.blank_lines);
+    println!("Rejected lines: {}", report.rejects.len());
+    println!("Coverage: {:.1}%", report.coverage() * 100.0);
+    for reject in &report.rejects {
+        println!(
+            "  line {} ({}): {}",
+            reject.line_number, reject.reason, reject.content
+        );
+    }
+}
+
+/// Prints the distinctive words and phrases of each user, users sorted by name.
+pub fn print_distinctive(terms: HashMap<String, DistinctiveTerms>) {
+    let mut sorted: Vec<_> = terms.into_iter().collect();
+    sorted.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (user, terms) in sorted {
+        println!("{}:", user);
+        println!("  words: {}", terms.words.join(", "));
+        println!("  phrases: {}", terms.phrases.join(", "));
+    }
+}
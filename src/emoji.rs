@@ -0,0 +1,45 @@
+use crate::utils::text_utils::blank_spans;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Finds the emoji in a text, with their byte range in it and their fully
+/// qualified form, e.g. `❤️` for a `❤` written without a variation selector.
+///
+/// Emoji are read a grapheme cluster at a time, so that ZWJ sequences such
+/// as `👨‍👩‍👧`, skin tones and flags each count as one emoji. Symbols like `©`
+/// and `™` are only taken for emoji when written with the variation selector,
+/// as they usually stand for themselves.
+fn emoji_spans(text: &str) -> Vec<(Range<usize>, &'static str)> {
+    text.grapheme_indices(true)
+        .filter(|(_, grapheme)| !is_text_symbol(grapheme))
+        .filter_map(|(start, grapheme)| {
+            let emoji = emojis::get(grapheme)?;
+            Some((start..start + grapheme.len(), emoji.as_str()))
+        })
+        .collect()
+}
+
+/// Checks for a lone symbol from the Latin-1, punctuation or letterlike
+/// blocks, such as `©`, `®` or `™`, written without the variation selector.
+fn is_text_symbol(grapheme: &str) -> bool {
+    let mut chars = grapheme.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => c < '\u{2190}',
+        _ => false,
+    }
+}
+
+/// Returns the emoji in a text in the order they appear, in their fully
+/// qualified form.
+pub fn extract_emoji(text: &str) -> Vec<String> {
+    emoji_spans(text)
+        .into_iter()
+        .map(|(_, emoji)| emoji.to_string())
+        .collect()
+}
+
+/// Returns the text with its emoji replaced by spaces, so that emoji glued
+/// to a word aren't counted as part of it.
+pub fn strip_emoji(text: &str) -> String {
+    blank_spans(text, emoji_spans(text).into_iter().map(|(span, _)| span))
+}
@@ -1,3 +1,4 @@
+use crate::diagnostics::RejectReason;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -7,6 +8,50 @@ pub enum ParseError {
 
     #[error("Does not contain split rule")]
     NoSplitter,
+
+    #[error("Could not read the archive: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("Could not read the JSON export: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Could not read the CSV file: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("The CSV file has no \"{0}\" column")]
+    MissingCsvColumn(String),
+
+    #[error("Invalid CSV columns \"{0}\", expected TIMESTAMP,SENDER,BODY")]
+    InvalidCsvColumns(String),
+
+    #[error("Line {line_number}: could not read the timestamp \"{value}\"")]
+    InvalidTimestamp { line_number: usize, value: String },
+
+    #[error("Entry {entry_number}: could not read the timestamp \"{value}\"")]
+    InvalidEntryTimestamp { entry_number: usize, value: String },
+
+    #[error("The archive does not contain a chat .txt file")]
+    NoChatInArchive,
+
+    #[error("Unknown date format \"{0}\", expected one of: dmy, mdy, ymd")]
+    UnknownDateOrder(String),
+
+    #[error(
+        "Could not tell whether dates are day/month/year or month/day/year, pass --date-format dmy or --date-format mdy"
+    )]
+    AmbiguousDateOrder,
+
+    #[error("Dates in the file disagree on whether the day or the month comes first")]
+    ConflictingDateOrder,
+
+    #[error("Line {0} is not valid UTF-8 or UTF-16 text")]
+    InvalidEncoding(usize),
+
+    #[error("Line {line_number}: {reason}")]
+    UnparseableLine {
+        line_number: usize,
+        reason: RejectReason,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -4,9 +4,13 @@ use std::fs;
 use crate::{
     message::Message,
     stats::{
-        average_messages_per_user, average_words_per_message, longest_message_length,
-        messages_per_user, most_active_hour, top_speaker_per_hour, total_word_count, words_sent,
+        DistinctiveTerms, average_messages_per_user, average_words_per_message,
+        distinctive_terms_per_user, domains_per_user, edits_per_user, emoji_count,
+        emoji_per_message, emoji_per_user, links_per_domain, longest_message_length,
+        messages_per_user, most_active_hour, ngram_count, top_speaker_per_hour, total_word_count,
+        user_messages, words_sent,
     },
+    tokenizer::Tokenizer,
 };
 
 /// Template context for rendering the dashboard.
@@ -18,6 +22,10 @@ struct DashboardTemplate<'a> {
     values: &'a [i32],
     words: &'a [String],
     words_count: &'a [i64],
+    bigrams: &'a [String],
+    bigrams_count: &'a [i64],
+    trigrams: &'a [String],
+    trigrams_count: &'a [i64],
     left_schedule: &'a [(String, String)],
     right_schedule: &'a [(String, String)],
     words_sent: &'a usize,
@@ -27,55 +35,171 @@ struct DashboardTemplate<'a> {
     longest_message: &'a usize,
     average_word: &'a f64,
     average_message: &'a f64,
+    edits: &'a [(String, i64, i64)],
+    domains: &'a [(String, i64, String)],
+    user_cards: &'a [(String, DistinctiveTerms)],
+    top_emoji: &'a [(String, i64)],
+    user_emoji: &'a [(String, String, f64)],
 }
 
 /// Prepares sorted user activity data (names and counts).
 fn prepare_user_activity(messages: &[Message]) -> (Vec<String>, Vec<i32>) {
-    let user_activity = messages_per_user(&messages).unwrap();
+    let user_activity = messages_per_user(messages).unwrap();
     let mut data: Vec<(String, i32)> = user_activity
         .into_iter()
         .map(|(k, v)| (k, v as i32))
         .collect();
-    data.sort_by(|a, b| b.1.cmp(&a.1));
+    data.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
     data.into_iter().unzip()
 }
 
 /// Prepares the top-N most used words and their counts.
-fn prepare_word_frequencies(messages: &[Message], top_n: usize) -> (Vec<String>, Vec<i64>) {
-    let mut word_counts: Vec<_> = total_word_count(messages).unwrap().into_iter().collect();
-    word_counts.sort_by(|a, b| b.1.cmp(&a.1));
-    word_counts
+fn prepare_word_frequencies(
+    messages: &[Message],
+    tokenizer: &Tokenizer,
+    top_n: usize,
+) -> (Vec<String>, Vec<i64>) {
+    let mut word_counts: Vec<_> = total_word_count(messages, tokenizer)
+        .unwrap()
+        .into_iter()
+        .collect();
+    word_counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    word_counts.into_iter().take(top_n).unzip()
+}
+
+/// Prepares the top-N most used `n`-word phrases and their counts.
+fn prepare_ngrams(
+    messages: &[Message],
+    n: usize,
+    tokenizer: &Tokenizer,
+    top_n: usize,
+) -> (Vec<String>, Vec<i64>) {
+    let mut ngrams: Vec<_> = ngram_count(messages, n, tokenizer)
+        .unwrap()
         .into_iter()
-        .take(top_n)
-        .map(|(word, count)| (word, count))
-        .unzip()
+        .collect();
+    ngrams.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ngrams.into_iter().take(top_n).unzip()
 }
 
+/// One half of the hourly schedule: `(hour, top speaker)` pairs.
+type Schedule = Vec<(String, String)>;
+
 /// Prepares two halves (0–11, 12–23) of the top speaker schedule by hour.
-fn prepare_top_speaker_schedule(
+/// Hours without any message are listed with an empty name.
+fn prepare_top_speaker_schedule(messages: &[Message]) -> (Schedule, Schedule) {
+    let top_speakers = top_speaker_per_hour(messages).unwrap();
+    let mut entries: Schedule = (0..24)
+        .map(|hour| {
+            let hour = format!("{:02}", hour);
+            let speaker = top_speakers.get(&hour).cloned().unwrap_or_default();
+            (hour, speaker)
+        })
+        .collect();
+    let right = entries.split_off(12);
+    (entries, right)
+}
+
+/// Prepares `(user, deleted, edited)` rows, most deletions first.
+fn prepare_edits(messages: &[Message]) -> Vec<(String, i64, i64)> {
+    let mut rows: Vec<_> = edits_per_user(messages)
+        .unwrap()
+        .into_iter()
+        .map(|(user, counts)| (user, counts.deleted, counts.edited))
+        .collect();
+    rows.sort_by_key(|(_, deleted, edited)| std::cmp::Reverse((*deleted, *edited)));
+    rows
+}
+
+/// Prepares `(domain, links, user who shared it most)` rows for the top-N
+/// most shared domains.
+fn prepare_domains(messages: &[Message], top_n: usize) -> Vec<(String, i64, String)> {
+    let per_user = domains_per_user(messages).unwrap();
+    let mut rows: Vec<_> = links_per_domain(messages)
+        .unwrap()
+        .into_iter()
+        .map(|(domain, count)| {
+            let sharer = per_user
+                .iter()
+                .filter_map(|(user, domains)| domains.get(&domain).map(|n| (*n, user)))
+                .max()
+                .map(|(_, user)| user.clone())
+                .unwrap_or_default();
+            (domain, count, sharer)
+        })
+        .collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    rows.truncate(top_n);
+    rows
+}
+
+/// Prepares the distinctive words and phrases of each user, in the order of
+/// `names`.
+fn prepare_user_cards(
     messages: &[Message],
-) -> (Vec<(String, String)>, Vec<(String, String)>) {
-    let mut entries: Vec<_> = top_speaker_per_hour(messages)
+    tokenizer: &Tokenizer,
+    names: &[String],
+) -> Vec<(String, DistinctiveTerms)> {
+    let mut terms = distinctive_terms_per_user(messages, tokenizer, 5).unwrap();
+    names
+        .iter()
+        .map(|name| (name.clone(), terms.remove(name).unwrap_or_default()))
+        .collect()
+}
+
+/// `(user, favorite emoji, emoji per message)` rows of the emoji panel.
+type EmojiRows = Vec<(String, String, f64)>;
+
+/// Prepares the top-N most sent emoji with their counts, and the rows of each
+/// user, heaviest users first.
+/// A user's favorites are their three most sent emoji.
+fn prepare_emoji(messages: &[Message], top_n: usize) -> (Vec<(String, i64)>, EmojiRows) {
+    let mut top: Vec<_> = emoji_count(messages).unwrap().into_iter().collect();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top.truncate(top_n);
+
+    let per_user = emoji_per_user(messages).unwrap();
+    let mut rows: Vec<_> = emoji_per_message(messages)
         .unwrap()
         .into_iter()
+        .map(|(user, ratio)| {
+            let mut favorites: Vec<_> = per_user.get(&user).into_iter().flatten().collect();
+            favorites.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+            let favorites: String = favorites
+                .into_iter()
+                .take(3)
+                .map(|(emoji, _)| emoji.as_str())
+                .collect();
+            (user, favorites, ratio)
+        })
         .collect();
-    entries.sort_by_key(|(hour, _)| hour.parse::<i64>().unwrap_or(0));
-    let left = entries[0..12].to_vec();
-    let right = entries[12..24].to_vec();
-    (left, right)
+    rows.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+    (top, rows)
 }
 
 /// Generates the dashboard HTML and writes it to `output/index.html`.
 /// This function aggregates statistics, prepares the template context,
-/// and renders the final dashboard using Askama.
-pub fn generate_html(messages: &[Message]) -> Result<(), Box<dyn std::error::Error>> {
+/// and renders the final dashboard using Askama. Words are counted as split
+/// by `tokenizer`.
+pub fn generate_html(
+    messages: &[Message],
+    tokenizer: &Tokenizer,
+) -> Result<(), Box<dyn std::error::Error>> {
     let (names, values) = prepare_user_activity(messages);
-    let (words, words_count) = prepare_word_frequencies(messages, 20);
+    let (words, words_count) = prepare_word_frequencies(messages, tokenizer, 20);
+    let (bigrams, bigrams_count) = prepare_ngrams(messages, 2, tokenizer, 15);
+    let (trigrams, trigrams_count) = prepare_ngrams(messages, 3, tokenizer, 15);
     let (left_schedule, right_schedule) = prepare_top_speaker_schedule(messages);
+    let edits = prepare_edits(messages);
+    let domains = prepare_domains(messages, 10);
+    let (top_emoji, user_emoji) = prepare_emoji(messages, 10);
+    let user_cards = prepare_user_cards(messages, tokenizer, &names);
 
     let words_sent = words_sent(messages).unwrap();
-    let messages_sent = messages.len();
-    let active_user = &names[0];
+    let messages_sent = user_messages(messages).count();
+    let active_user = names
+        .first()
+        .ok_or("No messages to build the dashboard from")?;
     let active_hour = most_active_hour(messages).unwrap();
     let average_message: f64 = average_messages_per_user(messages).unwrap().round();
     let average_word: f64 = average_words_per_message(messages).unwrap().round();
@@ -86,6 +210,10 @@ pub fn generate_html(messages: &[Message]) -> Result<(), Box<dyn std::error::Err
         values: &values,
         words: &words,
         words_count: &words_count,
+        bigrams: &bigrams,
+        bigrams_count: &bigrams_count,
+        trigrams: &trigrams,
+        trigrams_count: &trigrams_count,
         left_schedule: &left_schedule,
         right_schedule: &right_schedule,
         words_sent: &words_sent,
@@ -95,6 +223,11 @@ pub fn generate_html(messages: &[Message]) -> Result<(), Box<dyn std::error::Err
         average_message: &average_message,
         average_word: &average_word,
         longest_message: &longest_message,
+        edits: &edits,
+        domains: &domains,
+        user_cards: &user_cards,
+        top_emoji: &top_emoji,
+        user_emoji: &user_emoji,
     };
 
     fs::create_dir_all("./output")?;
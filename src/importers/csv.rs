@@ -0,0 +1,229 @@
+use crate::diagnostics::{ParseReport, RejectReason, RejectedLine};
+use crate::errors::ParseError;
+use crate::importers::date_and_hour;
+use crate::input;
+use crate::link::extract_links;
+use crate::mention::resolve_mentions;
+use crate::message::{Message, MessageKind};
+use crate::source::ChatSource;
+use crate::system::SystemEvent;
+use chrono::{DateTime, NaiveDateTime};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Formats timestamps are tried in when no format is given, after RFC 3339.
+const TIMESTAMP_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Unix times from this value on are taken as milliseconds rather than seconds.
+const UNIX_MILLIS_FROM: i64 = 100_000_000_000;
+
+/// Names of the CSV columns holding the fields of a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvColumns {
+    pub timestamp: String,
+    pub sender: String,
+    pub body: String,
+}
+
+impl Default for CsvColumns {
+    fn default() -> Self {
+        CsvColumns {
+            timestamp: "timestamp".to_string(),
+            sender: "sender".to_string(),
+            body: "body".to_string(),
+        }
+    }
+}
+
+impl FromStr for CsvColumns {
+    type Err = ParseError;
+
+    /// Reads the column names as `TIMESTAMP,SENDER,BODY`, e.g. `Date,Author,Content`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split(',').map(str::trim).collect::<Vec<_>>()[..] {
+            [timestamp, sender, body] => Ok(CsvColumns {
+                timestamp: timestamp.to_string(),
+                sender: sender.to_string(),
+                body: body.to_string(),
+            }),
+            _ => Err(ParseError::InvalidCsvColumns(s.to_string())),
+        }
+    }
+}
+
+/// Reads chats from CSV files with a header row and one message per row,
+/// as written by Signal backup tools, Discord exporters or custom scripts.
+pub struct CsvImporter<'a> {
+    filepath: &'a Path,
+    columns: CsvColumns,
+    timestamp_format: Option<String>,
+    strict: bool,
+}
+
+impl<'a> CsvImporter<'a> {
+    pub fn new(filepath: &'a Path) -> Self {
+        CsvImporter {
+            filepath,
+            columns: CsvColumns::default(),
+            timestamp_format: None,
+            strict: false,
+        }
+    }
+
+    /// Reads the message fields from the given columns instead of
+    /// `timestamp`, `sender` and `body`.
+    pub fn with_columns(mut self, columns: CsvColumns) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Reads timestamps in the given `chrono` format, e.g. `%d/%m/%Y %H:%M`,
+    /// instead of detecting RFC 3339, ISO 8601 and Unix times.
+    pub fn with_timestamp_format(mut self, format: &str) -> Self {
+        self.timestamp_format = Some(format.to_string());
+        self
+    }
+
+    /// Fails on the first row that can't be read, instead of skipping it and
+    /// listing it in the report.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Fails with `error` in strict mode, or else lists the row in `rejects`.
+    fn skip_row(
+        &self,
+        rejects: &mut Vec<RejectedLine>,
+        reject: RejectedLine,
+        error: ParseError,
+    ) -> Result<(), ParseError> {
+        if self.strict {
+            return Err(error);
+        }
+        rejects.push(reject);
+        Ok(())
+    }
+
+    /// Reads a timestamp in the configured format, or else as RFC 3339, ISO
+    /// 8601 or Unix time. Offsets are dropped keeping the written local time,
+    /// Unix times are read as UTC.
+    fn parse_timestamp(&self, raw: &str) -> Option<NaiveDateTime> {
+        let raw = raw.trim();
+        if let Some(format) = &self.timestamp_format {
+            return NaiveDateTime::parse_from_str(raw, format).ok();
+        }
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(raw) {
+            return Some(timestamp.naive_local());
+        }
+        if let Some(timestamp) = TIMESTAMP_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+        {
+            return Some(timestamp);
+        }
+        let unix_time: i64 = raw.parse().ok()?;
+        let timestamp = if unix_time.abs() >= UNIX_MILLIS_FROM {
+            DateTime::from_timestamp_millis(unix_time)
+        } else {
+            DateTime::from_timestamp(unix_time, 0)
+        };
+        timestamp.map(|timestamp| timestamp.naive_utc())
+    }
+}
+
+impl ChatSource for CsvImporter<'_> {
+    /// Parses the file into messages, counting each row as one line of the
+    /// report.
+    ///
+    /// Columns are matched ignoring case. Rows without a sender become
+    /// system events. The path may be `-` to read standard input, and the
+    /// file may be gzip or zstd compressed.
+    ///
+    /// Rows missing a mapped column or whose timestamp can't be read are
+    /// skipped and listed in the report's rejects. In [`CsvImporter::strict`]
+    /// mode they fail with `Err(ParseError::UnparseableLine)` and
+    /// `Err(ParseError::InvalidTimestamp)` instead.
+    ///
+    /// Returns `Err(ParseError::MissingCsvColumn)` if a mapped column is not
+    /// in the header.
+    fn parse_with_report(&self) -> Result<(Vec<Message>, ParseReport), ParseError> {
+        let reader = input::decode(input::open(self.filepath)?)?;
+        let mut reader = ::csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(reader);
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header.trim().eq_ignore_ascii_case(name))
+                .ok_or_else(|| ParseError::MissingCsvColumn(name.to_string()))
+        };
+        let timestamp_column = column(&self.columns.timestamp)?;
+        let sender_column = column(&self.columns.sender)?;
+        let body_column = column(&self.columns.body)?;
+
+        let mut messages = Vec::new();
+        let mut rejects = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let line_number = record.position().map_or(0, |p| p.line() as usize);
+            let rejected = |reason| RejectedLine {
+                line_number,
+                content: record.iter().collect::<Vec<_>>().join(","),
+                reason,
+            };
+            let fields = [timestamp_column, sender_column, body_column].map(|i| record.get(i));
+            let [Some(raw_timestamp), Some(owner), Some(text)] = fields else {
+                let reason = RejectReason::MissingFields;
+                let error = ParseError::UnparseableLine {
+                    line_number,
+                    reason,
+                };
+                self.skip_row(&mut rejects, rejected(reason), error)?;
+                continue;
+            };
+            let Some(timestamp) = self.parse_timestamp(raw_timestamp) else {
+                let error = ParseError::InvalidTimestamp {
+                    line_number,
+                    value: raw_timestamp.to_string(),
+                };
+                self.skip_row(
+                    &mut rejects,
+                    rejected(RejectReason::InvalidTimestamp),
+                    error,
+                )?;
+                continue;
+            };
+            let (date, hour) = date_and_hour(&timestamp);
+            let owner = owner.trim().to_string();
+            let text = text.trim().to_string();
+            let (kind, links) = if owner.is_empty() {
+                (
+                    MessageKind::System(SystemEvent::Other(text.clone())),
+                    Vec::new(),
+                )
+            } else {
+                (MessageKind::User, extract_links(&text))
+            };
+            messages.push(Message {
+                date,
+                hour,
+                owner,
+                text,
+                timestamp: Some(timestamp),
+                kind,
+                links,
+                ..Default::default()
+            });
+        }
+        resolve_mentions(&mut messages);
+        let report = ParseReport::of_entries(&messages).with_rejects(rejects);
+        Ok((messages, report))
+    }
+}
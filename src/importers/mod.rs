@@ -0,0 +1,13 @@
+use chrono::NaiveDateTime;
+
+pub mod csv;
+pub mod telegram;
+
+/// Splits a timestamp into the `date` and `hour` fields of a message, as
+/// `YYYY-MM-DD` and `HH:MM:SS`.
+pub(crate) fn date_and_hour(timestamp: &NaiveDateTime) -> (String, String) {
+    (
+        timestamp.format("%Y-%m-%d").to_string(),
+        timestamp.format("%H:%M:%S").to_string(),
+    )
+}
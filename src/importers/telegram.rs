@@ -0,0 +1,246 @@
+use crate::attachment::{Attachment, AttachmentKind};
+use crate::diagnostics::{ParseReport, RejectReason, RejectedLine};
+use crate::errors::ParseError;
+use crate::importers::date_and_hour;
+use crate::input;
+use crate::link::extract_links;
+use crate::mention::resolve_mentions;
+use crate::message::{Message, MessageKind};
+use crate::source::ChatSource;
+use crate::system::SystemEvent;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Format of the `date` field of exported messages, in local time.
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Name Telegram leaves out for senders whose account was deleted.
+const DELETED_ACCOUNT: &str = "Deleted Account";
+
+/// Reads the `result.json` file Telegram Desktop writes when exporting a
+/// single chat as JSON.
+pub struct TelegramImporter<'a> {
+    filepath: &'a Path,
+    strict: bool,
+}
+
+#[derive(Deserialize)]
+struct Export {
+    messages: Vec<ExportedMessage>,
+}
+
+#[derive(Deserialize)]
+struct ExportedMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    date: String,
+    from: Option<String>,
+    actor: Option<String>,
+    action: Option<String>,
+    title: Option<String>,
+    #[serde(default)]
+    members: Vec<Option<String>>,
+    #[serde(default)]
+    text: Text,
+    edited: Option<String>,
+    photo: Option<String>,
+    file: Option<String>,
+    media_type: Option<String>,
+    contact_information: Option<serde_json::Value>,
+}
+
+/// Message text, either plain or split into formatted parts such as links,
+/// mentions and bold text.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Text {
+    Plain(String),
+    Parts(Vec<TextPart>),
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Text::Plain(String::new())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TextPart {
+    Plain(String),
+    Entity { text: String },
+}
+
+impl Text {
+    /// Joins the parts of the text back into plain text.
+    fn flatten(self) -> String {
+        match self {
+            Text::Plain(text) => text,
+            Text::Parts(parts) => parts
+                .into_iter()
+                .map(|part| match part {
+                    TextPart::Plain(text) | TextPart::Entity { text } => text,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<'a> TelegramImporter<'a> {
+    pub fn new(filepath: &'a Path) -> Self {
+        TelegramImporter {
+            filepath,
+            strict: false,
+        }
+    }
+
+    /// Fails with `ParseError::InvalidTimestamp` on the first message whose
+    /// date can't be read, instead of skipping it and listing it in the report.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+}
+
+impl ChatSource for TelegramImporter<'_> {
+    /// Parses the export into messages, counting each exported message as
+    /// one line of the report.
+    ///
+    /// Formatted text is flattened to plain text, media is turned into
+    /// attachments and service messages into system events. The path may be
+    /// `-` to read standard input, and the file may be gzip or zstd compressed.
+    /// Messages whose date can't be read are skipped and listed in the
+    /// report's rejects, numbered by their position in the export.
+    fn parse_with_report(&self) -> Result<(Vec<Message>, ParseReport), ParseError> {
+        let reader = input::decode(input::open(self.filepath)?)?;
+        let export: Export = serde_json::from_reader(reader)?;
+        let mut messages = Vec::new();
+        let mut rejects = Vec::new();
+        for (index, exported) in export.messages.into_iter().enumerate() {
+            let Ok(timestamp) = NaiveDateTime::parse_from_str(&exported.date, DATE_FORMAT) else {
+                let entry_number = index + 1;
+                if self.strict {
+                    return Err(ParseError::InvalidEntryTimestamp {
+                        entry_number,
+                        value: exported.date,
+                    });
+                }
+                rejects.push(RejectedLine {
+                    line_number: entry_number,
+                    content: exported.date,
+                    reason: RejectReason::InvalidTimestamp,
+                });
+                continue;
+            };
+            messages.push(exported.into_message(timestamp));
+        }
+        resolve_mentions(&mut messages);
+        let report = ParseReport::of_entries(&messages).with_rejects(rejects);
+        Ok((messages, report))
+    }
+}
+
+impl ExportedMessage {
+    fn into_message(self, timestamp: NaiveDateTime) -> Message {
+        let (date, hour) = date_and_hour(&timestamp);
+        let attachment = self.attachment();
+        let edited = self.edited.is_some();
+        let text = self.text.flatten();
+        let (owner, kind) = if self.kind == "service" {
+            let actor = self.actor.unwrap_or_else(|| DELETED_ACCOUNT.to_string());
+            let members: Vec<String> = self
+                .members
+                .into_iter()
+                .map(|member| member.unwrap_or_else(|| DELETED_ACCOUNT.to_string()))
+                .collect();
+            let event = service_event(
+                self.action.as_deref().unwrap_or_default(),
+                &actor,
+                members,
+                self.title,
+            );
+            (actor, MessageKind::System(event))
+        } else {
+            let owner = self.from.unwrap_or_else(|| DELETED_ACCOUNT.to_string());
+            (owner, MessageKind::User)
+        };
+        let links = match &kind {
+            MessageKind::User => extract_links(&text),
+            MessageKind::System(_) => Vec::new(),
+        };
+        Message {
+            date,
+            hour,
+            owner,
+            text,
+            timestamp: Some(timestamp),
+            kind,
+            attachment,
+            deleted: false,
+            edited,
+            mentions: Vec::new(),
+            links,
+        }
+    }
+
+    /// Reads the media attached to the message. Files left out of the export
+    /// are named with a note instead of a path, and get no file name.
+    fn attachment(&self) -> Option<Attachment> {
+        let (kind, path) = if let Some(photo) = &self.photo {
+            (AttachmentKind::Image, photo)
+        } else if let Some(file) = &self.file {
+            let kind = match self.media_type.as_deref() {
+                Some("sticker") => AttachmentKind::Sticker,
+                Some("animation") => AttachmentKind::Gif,
+                Some("video_file" | "video_message") => AttachmentKind::Video,
+                Some("voice_message" | "audio_file") => AttachmentKind::Audio,
+                _ => AttachmentKind::Document,
+            };
+            (kind, file)
+        } else if self.contact_information.is_some() {
+            return Some(Attachment {
+                kind: AttachmentKind::Contact,
+                file_name: None,
+            });
+        } else {
+            return None;
+        };
+        let file_name =
+            (!path.starts_with('(')).then(|| path.rsplit('/').next().unwrap_or(path).to_string());
+        Some(Attachment { kind, file_name })
+    }
+}
+
+/// Maps a service message action onto the matching group event.
+fn service_event(
+    action: &str,
+    actor: &str,
+    members: Vec<String>,
+    title: Option<String>,
+) -> SystemEvent {
+    let actor = actor.to_string();
+    let only_actor = members.len() == 1 && members[0] == actor;
+    match action {
+        "create_group" | "create_channel" => SystemEvent::GroupCreated {
+            actor,
+            subject: title.unwrap_or_default(),
+        },
+        "invite_members" if only_actor => SystemEvent::MemberJoined { member: actor },
+        "invite_members" => SystemEvent::MemberAdded { actor, members },
+        "join_group_by_link" | "join_group_by_request" => {
+            SystemEvent::MemberJoined { member: actor }
+        }
+        "remove_members" if only_actor => SystemEvent::MemberLeft { member: actor },
+        "remove_members" => SystemEvent::MemberRemoved {
+            actor,
+            member: members.join(", "),
+        },
+        "edit_group_title" => SystemEvent::SubjectChanged {
+            actor,
+            subject: title.unwrap_or_default(),
+        },
+        "edit_group_photo" | "delete_group_photo" => SystemEvent::IconChanged { actor },
+        _ => SystemEvent::Other(action.to_string()),
+    }
+}
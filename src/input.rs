@@ -0,0 +1,225 @@
+use crate::errors::ParseError;
+use flate2::read::MultiGzDecoder;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Path that stands for standard input instead of a file.
+pub const STDIN_PATH: &str = "-";
+
+/// Bytes every gzip stream starts with.
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// Bytes every zstd frame starts with.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Checks whether `path` stands for standard input.
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == STDIN_PATH
+}
+
+/// Opens the file at `path`, or standard input for `-`, for buffered reading.
+///
+/// Gzip and zstd compressed input is decompressed on the fly. The compression
+/// is told by the first bytes of the input, whatever the file is named.
+pub fn open(path: &Path) -> Result<Box<dyn BufRead>, ParseError> {
+    let mut reader: Box<dyn BufRead> = if is_stdin(path) {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(path)?))
+    };
+    let head = reader.fill_buf()?;
+    if head.starts_with(GZIP_MAGIC) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else if head.starts_with(ZSTD_MAGIC) {
+        Ok(Box::new(BufReader::new(zstd::Decoder::with_buffer(
+            reader,
+        )?)))
+    } else {
+        Ok(reader)
+    }
+}
+
+/// Byte order mark at the start of UTF-8 text.
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+
+/// Byte order marks at the start of UTF-16 text.
+const UTF16_LE_BOM: &[u8] = &[0xff, 0xfe];
+const UTF16_BE_BOM: &[u8] = &[0xfe, 0xff];
+
+/// Text encodings exports are read in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Encoding {
+    /// Tells the encoding from the first bytes of an input, returning it
+    /// together with the length of the byte order mark to skip.
+    ///
+    /// Without a byte order mark, UTF-16 is recognized by the zero bytes
+    /// ASCII characters leave in every other byte. Anything else is UTF-8.
+    pub fn detect(head: &[u8]) -> (Encoding, usize) {
+        if head.starts_with(UTF8_BOM) {
+            return (Encoding::Utf8, UTF8_BOM.len());
+        }
+        if head.starts_with(UTF16_LE_BOM) {
+            return (Encoding::Utf16Le, UTF16_LE_BOM.len());
+        }
+        if head.starts_with(UTF16_BE_BOM) {
+            return (Encoding::Utf16Be, UTF16_BE_BOM.len());
+        }
+        let pairs: Vec<&[u8]> = head.chunks_exact(2).take(64).collect();
+        let zeros_at = |index: usize| pairs.iter().filter(|pair| pair[index] == 0).count();
+        let (even_zeros, odd_zeros) = (zeros_at(0), zeros_at(1));
+        if !pairs.is_empty() && odd_zeros * 2 > pairs.len() && even_zeros == 0 {
+            (Encoding::Utf16Le, 0)
+        } else if !pairs.is_empty() && even_zeros * 2 > pairs.len() && odd_zeros == 0 {
+            (Encoding::Utf16Be, 0)
+        } else {
+            (Encoding::Utf8, 0)
+        }
+    }
+}
+
+/// Detects the encoding of `reader` and returns it as UTF-8 text, skipping
+/// the byte order mark and transcoding UTF-16 as it is read.
+pub fn decode(mut reader: Box<dyn BufRead>) -> Result<Box<dyn BufRead>, ParseError> {
+    let (encoding, bom_len) = Encoding::detect(reader.fill_buf()?);
+    reader.consume(bom_len);
+    match encoding {
+        Encoding::Utf8 => Ok(reader),
+        Encoding::Utf16Le | Encoding::Utf16Be => Ok(Box::new(BufReader::new(Utf16Decoder {
+            inner: reader,
+            big_endian: encoding == Encoding::Utf16Be,
+            decoded: Vec::new(),
+            position: 0,
+            error: None,
+        }))),
+    }
+}
+
+/// Reads the next line into `line` like [`BufRead::read_line`], failing with
+/// `ParseError::InvalidEncoding` if the line is not valid text.
+pub fn read_line(
+    reader: &mut dyn BufRead,
+    line: &mut String,
+    line_number: usize,
+) -> Result<usize, ParseError> {
+    reader.read_line(line).map_err(|err| match err.kind() {
+        io::ErrorKind::InvalidData => ParseError::InvalidEncoding(line_number),
+        _ => ParseError::Io(err),
+    })
+}
+
+/// Reads the next line into `line` like [`read_line`], but writes the
+/// characters that can't be decoded as U+FFFD instead of failing.
+///
+/// Returns the number of bytes read, and whether the whole line was valid text.
+pub fn read_line_lossy(
+    reader: &mut dyn BufRead,
+    line: &mut String,
+) -> Result<(usize, bool), ParseError> {
+    let mut bytes = Vec::new();
+    let mut valid = true;
+    loop {
+        match reader.read_until(b'\n', &mut bytes) {
+            Ok(_) => break,
+            Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                valid = false;
+                bytes.extend_from_slice(char::REPLACEMENT_CHARACTER.to_string().as_bytes());
+            }
+            Err(err) => return Err(ParseError::Io(err)),
+        }
+    }
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(err) => {
+            valid = false;
+            String::from_utf8_lossy(err.as_bytes()).into_owned()
+        }
+    };
+    line.push_str(&text);
+    Ok((text.len(), valid))
+}
+
+/// Transcodes UTF-16 input to UTF-8 as it is read.
+struct Utf16Decoder<R> {
+    inner: R,
+    big_endian: bool,
+    /// UTF-8 bytes decoded but not read yet, from `position` on.
+    decoded: Vec<u8>,
+    position: usize,
+    /// Error hit while decoding, returned once the text before it is read.
+    error: Option<io::Error>,
+}
+
+impl<R: BufRead> Utf16Decoder<R> {
+    fn next_unit(&mut self) -> io::Result<Option<u16>> {
+        let mut bytes = [0u8; 2];
+        let mut read = 0;
+        while read < bytes.len() {
+            match self.inner.read(&mut bytes[read..])? {
+                0 => break,
+                n => read += n,
+            }
+        }
+        match read {
+            0 => Ok(None),
+            2 if self.big_endian => Ok(Some(u16::from_be_bytes(bytes))),
+            2 => Ok(Some(u16::from_le_bytes(bytes))),
+            _ => Err(invalid_utf16()),
+        }
+    }
+
+    fn next_char(&mut self) -> io::Result<Option<char>> {
+        let Some(unit) = self.next_unit()? else {
+            return Ok(None);
+        };
+        let decoded = if (0xd800..0xdc00).contains(&unit) {
+            let low = self.next_unit()?.ok_or_else(invalid_utf16)?;
+            char::decode_utf16([unit, low]).next()
+        } else {
+            char::decode_utf16([unit]).next()
+        };
+        match decoded {
+            Some(Ok(c)) => Ok(Some(c)),
+            _ => Err(invalid_utf16()),
+        }
+    }
+}
+
+impl<R: BufRead> Read for Utf16Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.decoded.len() {
+            if let Some(err) = self.error.take() {
+                return Err(err);
+            }
+            self.decoded.clear();
+            self.position = 0;
+            while self.decoded.len() < buf.len() {
+                match self.next_char() {
+                    Ok(Some(c)) => self
+                        .decoded
+                        .extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                    Ok(None) => break,
+                    Err(err) if self.decoded.is_empty() => return Err(err),
+                    Err(err) => {
+                        self.error = Some(err);
+                        break;
+                    }
+                }
+            }
+        }
+        let read = buf.len().min(self.decoded.len() - self.position);
+        buf[..read].copy_from_slice(&self.decoded[self.position..self.position + read]);
+        self.position += read;
+        Ok(read)
+    }
+}
+
+fn invalid_utf16() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid UTF-16")
+}
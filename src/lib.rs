@@ -1,7 +1,20 @@
+pub mod archive;
+pub mod attachment;
+pub mod diagnostics;
 pub mod display;
+pub mod emoji;
 pub mod errors;
 pub mod html;
+pub mod importers;
+pub mod input;
+pub mod link;
+pub mod mention;
+pub mod merge;
 pub mod message;
 pub mod parser;
+pub mod source;
 pub mod stats;
+pub mod stopwords;
+pub mod system;
+pub mod tokenizer;
 pub mod utils;
@@ -0,0 +1,97 @@
+use crate::utils::text_utils::blank_spans;
+use std::ops::Range;
+
+/// Starts of the links recognized in message texts.
+const LINK_PREFIXES: [&str; 3] = ["https://", "http://", "www."];
+
+/// Punctuation dropped from the end of a link, where it usually belongs to
+/// the sentence rather than to the address.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', '\'', '"', '>', ']', '}'];
+
+/// Finds the byte ranges of the links in a text.
+fn link_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut searched = 0;
+    for word in text.split_whitespace() {
+        let word_start = searched + text[searched..].find(word).unwrap_or(0);
+        searched = word_start + word.len();
+        let Some(offset) = link_start(word) else {
+            continue;
+        };
+        let link = trim_link(&word[offset..]);
+        if link_domain(link).is_some() {
+            spans.push(word_start + offset..word_start + offset + link.len());
+        }
+    }
+    spans
+}
+
+/// Finds where a link starts in a word, ignoring case. Links must not be
+/// glued to a letter or digit before them.
+fn link_start(word: &str) -> Option<usize> {
+    let mut previous: Option<char> = None;
+    for (index, c) in word.char_indices() {
+        let glued = previous.is_some_and(char::is_alphanumeric);
+        previous = Some(c);
+        let starts_link = LINK_PREFIXES.iter().any(|prefix| {
+            word.get(index..index + prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        });
+        if starts_link && !glued {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// Drops trailing punctuation from a link, and a closing parenthesis unless
+/// the link opened one, as Wikipedia links do.
+fn trim_link(link: &str) -> &str {
+    let mut link = link;
+    loop {
+        let trimmed = link.trim_end_matches(TRAILING_PUNCTUATION);
+        let trimmed = match trimmed.strip_suffix(')') {
+            Some(inner) if inner.matches('(').count() < trimmed.matches(')').count() => inner,
+            _ => trimmed,
+        };
+        if trimmed.len() == link.len() {
+            return link;
+        }
+        link = trimmed;
+    }
+}
+
+/// Returns the links shared in a text, as written.
+pub fn extract_links(text: &str) -> Vec<String> {
+    link_spans(text)
+        .into_iter()
+        .map(|span| text[span].to_string())
+        .collect()
+}
+
+/// Returns the text with its links removed, so that they aren't counted as words.
+pub fn strip_links(text: &str) -> String {
+    blank_spans(text, link_spans(text))
+}
+
+/// Returns the domain a link points to, lowercased and without `www.`,
+/// e.g. `youtube.com` for `https://www.YouTube.com/watch?v=1`.
+///
+/// Returns `None` if the link has no host with a dot in it.
+pub fn link_domain(link: &str) -> Option<String> {
+    let lower = link.to_lowercase();
+    let rest = ["https://", "http://"]
+        .iter()
+        .find_map(|scheme| lower.strip_prefix(scheme))
+        .unwrap_or(&lower);
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit('@').next()?.split(':').next()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let valid = host.contains('.')
+        && !host.starts_with('.')
+        && !host.ends_with('.')
+        && host
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '.' || c == '-');
+    valid.then(|| host.to_string())
+}
@@ -1,18 +1,36 @@
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command, error::ErrorKind};
 use std::path::Path;
 use whatsapp_stats::{
-    display::{pretty_print_top_speakers, print_hashmap},
+    archive::MediaIndex,
+    display::{
+        pretty_print_top_speakers, print_averages, print_breakdown, print_distinctive, print_edits,
+        print_hashmap, print_parse_report, print_timeline, print_top, print_top_breakdown,
+    },
+    errors::ParseError,
     html::html::generate_html,
+    importers::{
+        csv::{CsvColumns, CsvImporter},
+        telegram::TelegramImporter,
+    },
+    mention::resolve_mentions,
+    merge::merge_exports,
     message::Message,
     parser::Parser,
+    source::ChatSource,
     stats::{
-        count_phrase_per_user, count_word_per_user, extract_word_count, messages_per_user,
-        top_speaker_per_hour, total_word_count,
+        count_phrase_per_user, count_word_per_user, distinctive_terms_per_user, domains_per_user,
+        edits_per_user, emoji_count, emoji_per_message, emoji_per_user, extract_word_count,
+        links_per_domain, media_bytes_per_user, media_per_user, mentions_per_user,
+        messages_per_day, messages_per_month, messages_per_user, messages_per_weekday,
+        messages_per_year, ngram_count, ngrams_per_user, top_speaker_per_hour, total_word_count,
     },
+    stopwords::Stopwords,
+    tokenizer::Tokenizer,
+    utils::time_utils::DateOrder,
 };
 
 fn main() {
-    let matches = Command::new("whatsapp-stats")
+    let mut command = Command::new("whatsapp-stats")
         .version("1.0")
         .about("Blazingly fast WhatsApp chat analyzer in Rust 🚀")
         .arg(
@@ -20,9 +38,49 @@ fn main() {
                 .short('f')
                 .long("file")
                 .value_name("FILE")
-                .help("Path to WhatsApp export file")
+                .help("Path to WhatsApp export file (.txt or .zip, optionally gzip or zstd compressed), or - for stdin. Repeat to merge overlapping exports")
+                .action(ArgAction::Append)
                 .required(true),
         )
+        .arg(
+            Arg::new("date-format")
+                .long("date-format")
+                .value_name("ORDER")
+                .value_parser(["dmy", "mdy", "ymd"])
+                .help("Order of the date fields in a WhatsApp export, detected when omitted"),
+        )
+        .arg(
+            Arg::new("input-format")
+                .long("input-format")
+                .value_name("FORMAT")
+                .value_parser(["whatsapp", "telegram", "csv"])
+                .default_value("whatsapp")
+                .help("Format of the export: a WhatsApp chat, Telegram Desktop's result.json or a CSV file"),
+        )
+        .arg(
+            Arg::new("csv-columns")
+                .long("csv-columns")
+                .value_name("TIMESTAMP,SENDER,BODY")
+                .help("Names of the CSV columns holding each message field [default: timestamp,sender,body]"),
+        )
+        .arg(
+            Arg::new("csv-timestamp-format")
+                .long("csv-timestamp-format")
+                .value_name("FORMAT")
+                .help("chrono format of the CSV timestamps, detected when omitted"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .action(ArgAction::SetTrue)
+                .help("Fail on the first line or entry that can't be parsed instead of skipping it"),
+        )
+        .arg(
+            Arg::new("fold-accents")
+                .long("fold-accents")
+                .action(ArgAction::SetTrue)
+                .help("Count accented words as their plain form in word stats, e.g. café as cafe"),
+        )
         .subcommand_required(true)
         .subcommand(
             Command::new("word")
@@ -40,50 +98,414 @@ fn main() {
                 .arg(Arg::new("target").required(false)),
         )
         .subcommand(
-            Command::new("total-word-count").about("Count total word frequency across the chat"),
+            Command::new("total-word-count")
+                .about("Count total word frequency across the chat")
+                .args(stopword_args()),
+        )
+        .subcommand(
+            Command::new("ngrams")
+                .about("Most frequent phrases of a few words across the chat")
+                .arg(
+                    Arg::new("n")
+                        .long("n")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(u64).range(2..=5))
+                        .default_value("2")
+                        .help("Number of words in a phrase"),
+                )
+                .arg(
+                    Arg::new("top")
+                        .long("top")
+                        .value_name("COUNT")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("20")
+                        .help("Number of phrases to list"),
+                )
+                .arg(
+                    Arg::new("by-user")
+                        .long("by-user")
+                        .action(ArgAction::SetTrue)
+                        .help("List the phrases of each user"),
+                )
+                .args(stopword_args()),
+        )
+        .subcommand(
+            Command::new("distinctive")
+                .about("Words and phrases each user writes more than the rest of the group")
+                .arg(
+                    Arg::new("top")
+                        .long("top")
+                        .value_name("COUNT")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("5")
+                        .help("Number of words and of phrases to list for each user"),
+                )
+                .args(stopword_args()),
         )
         .subcommand(Command::new("top-speakers").about("Find top speakers per hour"))
         .subcommand(Command::new("user-activity").about("Messages sent by each user"))
-        .subcommand(Command::new("html").about("Generate html"))
-        .get_matches();
+        .subcommand(
+            Command::new("media")
+                .about("Media shared by each user, by type")
+                .arg(
+                    Arg::new("bytes")
+                        .long("bytes")
+                        .action(ArgAction::SetTrue)
+                        .help("Report bytes shared per user, using the media in a .zip export"),
+                ),
+        )
+        .subcommand(Command::new("edits").about("Deleted and edited messages of each user"))
+        .subcommand(Command::new("mentions").about("Who mentions whom with @"))
+        .subcommand(
+            Command::new("emoji")
+                .about("Most sent emoji")
+                .arg(
+                    Arg::new("by-user")
+                        .long("by-user")
+                        .action(ArgAction::SetTrue)
+                        .help("Break the emoji down by the user who sent them"),
+                )
+                .arg(
+                    Arg::new("per-message")
+                        .long("per-message")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("by-user")
+                        .help("Report the average number of emoji per message of each user"),
+                ),
+        )
+        .subcommand(
+            Command::new("links")
+                .about("Most shared link domains")
+                .arg(
+                    Arg::new("by-user")
+                        .long("by-user")
+                        .action(ArgAction::SetTrue)
+                        .help("Break the domains down by the user who shared them"),
+                ),
+        )
+        .subcommand(
+            Command::new("timeline")
+                .about("Messages sent per day, weekday, month or year")
+                .arg(
+                    Arg::new("by")
+                        .long("by")
+                        .value_parser(["day", "weekday", "month", "year"])
+                        .default_value("month"),
+                ),
+        )
+        .subcommand(
+            Command::new("html")
+                .about("Generate html")
+                .args(stopword_args()),
+        )
+        .subcommand(
+            Command::new("parse-check").about("Report how many lines of the export were parsed"),
+        );
+    let matches = command.get_matches_mut();
+    if let Some(message) = misplaced_format_option(&matches) {
+        command.error(ErrorKind::ArgumentConflict, message).exit();
+    }
 
-    let file_path: &str = matches.get_one::<String>("file").unwrap();
-    let parser = Parser::new(Path::new(file_path));
-    let messages: Vec<Message> = parser.parse().unwrap();
+    let file_paths: Vec<&String> = matches.get_many::<String>("file").unwrap().collect();
+    let mut sources: Vec<Box<dyn ChatSource>> = Vec::new();
+    for file_path in &file_paths {
+        match chat_source(&matches, file_path) {
+            Ok(source) => sources.push(source),
+            Err(err) => exit_with_parse_error(file_path, err),
+        }
+    }
+    if matches.subcommand_name() == Some("parse-check") {
+        for (file_path, source) in file_paths.iter().zip(&sources) {
+            if sources.len() > 1 {
+                println!("{}:", file_path);
+            }
+            match source.check() {
+                Ok(report) => print_parse_report(&report),
+                Err(err) => exit_with_parse_error(file_path, err),
+            }
+        }
+        return;
+    }
+    let mut tokenizer = Tokenizer::new();
+    if matches.get_flag("fold-accents") {
+        tokenizer = tokenizer.fold_accents();
+    }
+    let messages = if sources.len() == 1 {
+        match sources[0].stream() {
+            Ok(stream) => Messages {
+                file_path: file_paths[0],
+                stream,
+            },
+            Err(err) => exit_with_parse_error(file_paths[0], err),
+        }
+    } else {
+        let mut exports: Vec<Vec<Message>> = Vec::new();
+        for (file_path, source) in file_paths.iter().zip(&sources) {
+            match source.parse() {
+                Ok(messages) => exports.push(messages),
+                Err(err) => exit_with_parse_error(file_path, err),
+            }
+        }
+        let (messages, report) = merge_exports(exports);
+        eprintln!(
+            "Merged {} exports into {} messages, dropped {} duplicates",
+            report.exports, report.messages, report.duplicates
+        );
+        Messages {
+            file_path: file_paths[0],
+            stream: Box::new(messages.into_iter().map(Ok)),
+        }
+    };
 
     match matches.subcommand() {
         Some(("word", sub)) => {
             let word = sub.get_one::<String>("target").unwrap();
-            let stats = count_word_per_user(&messages, word).unwrap();
+            let stats = count_word_per_user(messages, word, &tokenizer).unwrap();
             println!("The times \"{}\" was said is:", word);
             print_hashmap(stats);
         }
         Some(("phrase", sub)) => {
             let phrase = sub.get_one::<String>("target").unwrap();
-            let stats = count_phrase_per_user(&messages, phrase).unwrap();
+            let stats = count_phrase_per_user(messages, phrase, &tokenizer).unwrap();
             println!("The times \"{}\" was said is:", phrase);
             print_hashmap(stats);
         }
         Some(("word-count", sub)) => {
             let word = sub.get_one::<String>("target").unwrap();
-            extract_word_count(&messages, word).unwrap();
+            extract_word_count(messages, word, &tokenizer).unwrap();
         }
-        Some(("total-word-count", _)) => {
-            let total = total_word_count(&messages).unwrap();
+        Some(("total-word-count", sub)) => {
+            let messages = messages.into_chat();
+            let tokenizer = tokenizer.with_stopwords(stopwords(sub, &messages));
+            let total = total_word_count(&messages, &tokenizer).unwrap();
             print_hashmap(total);
         }
+        Some(("ngrams", sub)) => {
+            let messages = messages.into_chat();
+            let tokenizer = tokenizer.with_stopwords(stopwords(sub, &messages));
+            let n = *sub.get_one::<u64>("n").unwrap() as usize;
+            let top = *sub.get_one::<usize>("top").unwrap();
+            if sub.get_flag("by-user") {
+                print_top_breakdown(ngrams_per_user(&messages, n, &tokenizer).unwrap(), top);
+            } else {
+                print_top(ngram_count(&messages, n, &tokenizer).unwrap(), top);
+            }
+        }
+        Some(("distinctive", sub)) => {
+            let messages = messages.into_chat();
+            let tokenizer = tokenizer.with_stopwords(stopwords(sub, &messages));
+            let top = *sub.get_one::<usize>("top").unwrap();
+            print_distinctive(distinctive_terms_per_user(&messages, &tokenizer, top).unwrap());
+        }
         Some(("top-speakers", _)) => {
-            let hour_speakers = top_speaker_per_hour(&messages).unwrap();
+            let hour_speakers = top_speaker_per_hour(messages).unwrap();
             pretty_print_top_speakers(&hour_speakers);
         }
         Some(("user-activity", _)) => {
-            let user_activity = messages_per_user(&messages).unwrap();
+            let user_activity = messages_per_user(messages).unwrap();
             print_hashmap(user_activity);
         }
-        Some(("html", _)) => {
-            generate_html(&messages[..]).unwrap();
+        Some(("media", sub)) => {
+            if sub.get_flag("bytes") {
+                let mut media = MediaIndex::default();
+                for source in &sources {
+                    media.extend(source.media_index().unwrap());
+                }
+                let bytes = media_bytes_per_user(messages, &media).unwrap();
+                print_hashmap(bytes);
+            } else {
+                let media = media_per_user(messages).unwrap();
+                print_breakdown(media);
+            }
+        }
+        Some(("mentions", _)) => {
+            let messages = messages.into_chat();
+            let mentions = mentions_per_user(&messages).unwrap();
+            print_breakdown(mentions);
+        }
+        Some(("emoji", sub)) => {
+            if sub.get_flag("by-user") {
+                print_breakdown(emoji_per_user(messages).unwrap());
+            } else if sub.get_flag("per-message") {
+                print_averages(emoji_per_message(messages).unwrap());
+            } else {
+                print_hashmap(emoji_count(messages).unwrap());
+            }
+        }
+        Some(("links", sub)) => {
+            if sub.get_flag("by-user") {
+                print_breakdown(domains_per_user(messages).unwrap());
+            } else {
+                print_hashmap(links_per_domain(messages).unwrap());
+            }
+        }
+        Some(("edits", _)) => {
+            let edits = edits_per_user(messages).unwrap();
+            print_edits(edits);
+        }
+        Some(("timeline", sub)) => {
+            let mut undated = 0;
+            let messages = messages.inspect(|message| {
+                if message.is_user() && message.timestamp.is_none() {
+                    undated += 1;
+                }
+            });
+            match sub.get_one::<String>("by").unwrap().as_str() {
+                "day" => print_timeline(messages_per_day(messages).unwrap()),
+                "weekday" => print_hashmap(messages_per_weekday(messages).unwrap()),
+                "year" => print_timeline(messages_per_year(messages).unwrap()),
+                _ => print_timeline(messages_per_month(messages).unwrap()),
+            }
+            if undated > 0 {
+                eprintln!(
+                    "Left {} messages without a readable date out of the timeline. If the dates don't tell whether the day or the month comes first, pass --date-format dmy or --date-format mdy",
+                    undated
+                );
+            }
+        }
+        Some(("html", sub)) => {
+            let messages = messages.into_chat();
+            let tokenizer = tokenizer.with_stopwords(stopwords(sub, &messages));
+            if let Err(err) = generate_html(&messages, &tokenizer) {
+                eprintln!("Could not generate the dashboard: {}", err);
+                std::process::exit(1);
+            }
         }
 
         _ => unreachable!(),
     }
 }
+
+/// Sets up the reader of the export at `file_path` for the `--input-format`
+/// and the options of that format.
+fn chat_source<'a>(
+    matches: &ArgMatches,
+    file_path: &'a str,
+) -> Result<Box<dyn ChatSource + 'a>, ParseError> {
+    let path = Path::new(file_path);
+    match matches.get_one::<String>("input-format").unwrap().as_str() {
+        "telegram" => {
+            let mut importer = TelegramImporter::new(path);
+            if matches.get_flag("strict") {
+                importer = importer.strict();
+            }
+            Ok(Box::new(importer))
+        }
+        "csv" => {
+            let mut importer = CsvImporter::new(path);
+            if matches.get_flag("strict") {
+                importer = importer.strict();
+            }
+            if let Some(columns) = matches.get_one::<String>("csv-columns") {
+                importer = importer.with_columns(columns.parse::<CsvColumns>()?);
+            }
+            if let Some(format) = matches.get_one::<String>("csv-timestamp-format") {
+                importer = importer.with_timestamp_format(format);
+            }
+            Ok(Box::new(importer))
+        }
+        _ => {
+            let mut parser = Parser::new(path);
+            if let Some(date_format) = matches.get_one::<String>("date-format") {
+                parser = parser.with_date_order(date_format.parse::<DateOrder>()?);
+            }
+            if matches.get_flag("strict") {
+                parser = parser.strict();
+            }
+            Ok(Box::new(parser))
+        }
+    }
+}
+
+/// Names an option given for an `--input-format` it doesn't apply to.
+fn misplaced_format_option(matches: &ArgMatches) -> Option<String> {
+    let input_format = matches.get_one::<String>("input-format").unwrap();
+    [
+        ("date-format", "whatsapp"),
+        ("csv-columns", "csv"),
+        ("csv-timestamp-format", "csv"),
+    ]
+    .into_iter()
+    .find(|(option, format)| format != input_format && matches.get_one::<String>(option).is_some())
+    .map(|(option, format)| format!("--{} only applies to --input-format {}", option, format))
+}
+
+/// Options choosing the stopwords left out of word frequencies.
+fn stopword_args() -> [Arg; 2] {
+    [
+        Arg::new("stopwords")
+            .long("stopwords")
+            .value_name("LANGUAGE|FILE")
+            .action(ArgAction::Append)
+            .help("Leave out the stopwords of a language (en, es, pt, fr, de, it, nl, ru) or of a file with one word per line. Repeatable. Detected from the chat when omitted"),
+        Arg::new("no-stopwords")
+            .long("no-stopwords")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("stopwords")
+            .help("Count every word, stopwords included"),
+    ]
+}
+
+/// Picks the stopwords given with `--stopwords`, none with `--no-stopwords`,
+/// or else the built-in ones of the language the chat is written in.
+fn stopwords(sub: &ArgMatches, messages: &[Message]) -> Stopwords {
+    if sub.get_flag("no-stopwords") {
+        return Stopwords::default();
+    }
+    let Some(values) = sub.get_many::<String>("stopwords") else {
+        return Stopwords::detect_language(messages)
+            .and_then(Stopwords::language)
+            .unwrap_or_default();
+    };
+    let mut stopwords = Stopwords::default();
+    for value in values {
+        match Stopwords::language(value).map_or_else(|| Stopwords::from_file(Path::new(value)), Ok)
+        {
+            Ok(list) => stopwords.extend(list),
+            Err(err) => {
+                let languages: Vec<&str> = Stopwords::languages().collect();
+                eprintln!(
+                    "{} is neither a language ({}) nor a stopword file: {}",
+                    value,
+                    languages.join(", "),
+                    err
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+    stopwords
+}
+
+/// The messages of the exports, parsed as the stats go through them. A
+/// parse error ends the program.
+struct Messages<'a> {
+    file_path: &'a str,
+    stream: Box<dyn Iterator<Item = Result<Message, ParseError>> + 'a>,
+}
+
+impl Messages<'_> {
+    /// Collects the whole chat for the stats that need all of it at once,
+    /// resolving mentions against all of its senders.
+    fn into_chat(self) -> Vec<Message> {
+        let mut messages: Vec<Message> = self.collect();
+        resolve_mentions(&mut messages);
+        messages
+    }
+}
+
+impl Iterator for Messages<'_> {
+    type Item = Message;
+
+    fn next(&mut self) -> Option<Message> {
+        match self.stream.next()? {
+            Ok(message) => Some(message),
+            Err(err) => exit_with_parse_error(self.file_path, err),
+        }
+    }
+}
+
+fn exit_with_parse_error(file_path: &str, err: ParseError) -> ! {
+    eprintln!("Could not parse {}: {}", file_path, err);
+    std::process::exit(1);
+}
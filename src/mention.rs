@@ -0,0 +1,163 @@
+use crate::message::Message;
+use crate::utils::text_utils::{blank_spans, normalize_name};
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// Isolate marks (U+2068 and U+2069) newer exports put around the name in a
+/// mention, so that `@Alice Smith` keeps its last name.
+const ISOLATE_START: char = '\u{2068}';
+const ISOLATE_END: char = '\u{2069}';
+
+/// Fewest digits a mention needs to be taken for a phone number.
+const MIN_PHONE_DIGITS: usize = 7;
+
+/// A mention found in a message text.
+struct MentionSpan {
+    /// Byte range of the mention in the text, `@` included.
+    range: Range<usize>,
+    /// The mentioned name or number, without the `@`.
+    name: String,
+}
+
+/// Finds the `@Name` and `@+44 7700 900123` mentions in a text, skipping `@`
+/// signs inside words such as e-mail addresses.
+fn mention_spans(text: &str) -> Vec<MentionSpan> {
+    let mut spans = Vec::new();
+    let mut previous: Option<char> = None;
+    for (start, c) in text.char_indices() {
+        let after_word = previous.is_some_and(|p| p.is_alphanumeric() || p == '_');
+        previous = Some(c);
+        if c != '@' || after_word {
+            continue;
+        }
+        let rest = &text[start + 1..];
+        let Some((len, name)) = isolated_name(rest)
+            .or_else(|| phone_number(rest))
+            .or_else(|| word(rest))
+        else {
+            continue;
+        };
+        spans.push(MentionSpan {
+            range: start..start + 1 + len,
+            name,
+        });
+    }
+    spans
+}
+
+/// Reads a name wrapped in isolate marks. Returns its length in bytes,
+/// marks included, and the name.
+fn isolated_name(rest: &str) -> Option<(usize, String)> {
+    let inner = rest.strip_prefix(ISOLATE_START)?;
+    let end = inner.find(ISOLATE_END)?;
+    let name = normalize_name(&inner[..end]);
+    let len = ISOLATE_START.len_utf8() + end + ISOLATE_END.len_utf8();
+    (!name.is_empty()).then_some((len, name))
+}
+
+/// Reads a phone number, allowing a leading `+` and single spaces or dashes
+/// between digits.
+fn phone_number(rest: &str) -> Option<(usize, String)> {
+    let mut len = 0;
+    let mut digits = 0;
+    let mut chars = rest.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let next_is_digit = chars.peek().is_some_and(|(_, next)| next.is_ascii_digit());
+        let accepted = match c {
+            '0'..='9' => {
+                digits += 1;
+                true
+            }
+            '+' => index == 0 && next_is_digit,
+            ' ' | '-' => digits > 0 && next_is_digit,
+            _ => false,
+        };
+        if !accepted {
+            break;
+        }
+        len = index + c.len_utf8();
+    }
+    (digits >= MIN_PHONE_DIGITS).then(|| (len, rest[..len].to_string()))
+}
+
+/// Reads a single-word name such as `Alice` or `bob_92`.
+fn word(rest: &str) -> Option<(usize, String)> {
+    let len = rest
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-')))
+        .unwrap_or(rest.len());
+    let name = rest[..len].trim_end_matches(['.', '-']);
+    (!name.is_empty()).then(|| (name.len(), name.to_string()))
+}
+
+/// Returns the names and numbers mentioned in a text, without the `@`.
+pub fn extract_mentions(text: &str) -> Vec<String> {
+    mention_spans(text)
+        .into_iter()
+        .map(|span| span.name)
+        .collect()
+}
+
+/// Returns the text with its mentions removed, so that they aren't counted
+/// as words.
+pub fn strip_mentions(text: &str) -> String {
+    blank_spans(text, mention_spans(text).into_iter().map(|span| span.range))
+}
+
+/// Matches a mention with the name of a participant of the chat.
+///
+/// Phone numbers match participants saved under the same number, whatever
+/// the spacing and with or without the country code. Names match a
+/// participant with the same name, or the only participant whose first name
+/// it is, ignoring case. Mentions matching no participant are kept as written.
+pub fn resolve_mention(mention: &str, participants: &[&str]) -> String {
+    let digits = |text: &str| -> String { text.chars().filter(char::is_ascii_digit).collect() };
+    let mention_digits = digits(mention);
+    let is_phone = mention_digits.len() >= MIN_PHONE_DIGITS
+        && mention
+            .chars()
+            .all(|c| c.is_ascii_digit() || " +-".contains(c));
+    let found = if is_phone {
+        participants.iter().find(|participant| {
+            let participant_digits = digits(participant);
+            participant_digits.len() >= MIN_PHONE_DIGITS
+                && (participant_digits.ends_with(&mention_digits)
+                    || mention_digits.ends_with(&participant_digits))
+        })
+    } else {
+        let first_name = |name: &str| {
+            let name = name.trim_start_matches('~').trim();
+            name.split_whitespace()
+                .next()
+                .unwrap_or(name)
+                .to_lowercase()
+        };
+        let mention_lower = mention.to_lowercase();
+        participants
+            .iter()
+            .find(|participant| participant.to_lowercase() == mention_lower)
+            .or_else(|| {
+                let mut matching = participants
+                    .iter()
+                    .filter(|participant| first_name(participant) == first_name(mention));
+                matching.next().filter(|_| matching.next().is_none())
+            })
+    };
+    found.map_or_else(|| mention.to_string(), |name| name.to_string())
+}
+
+/// Fills in the mentions of every user message, resolved against the
+/// senders of the given messages.
+pub fn resolve_mentions(messages: &mut [Message]) {
+    let owners: BTreeSet<String> = messages
+        .iter()
+        .filter(|message| message.is_user())
+        .map(|message| message.owner.clone())
+        .collect();
+    let participants: Vec<&str> = owners.iter().map(String::as_str).collect();
+    for message in messages.iter_mut().filter(|message| message.is_user()) {
+        message.mentions = extract_mentions(&message.text)
+            .iter()
+            .map(|mention| resolve_mention(mention, &participants))
+            .collect();
+    }
+}
@@ -0,0 +1,77 @@
+use crate::message::Message;
+use chrono::{NaiveDateTime, Timelike};
+use std::collections::HashMap;
+
+/// What merging several exports of a chat kept and dropped.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MergeReport {
+    pub exports: usize,
+    /// Messages in the merged chat.
+    pub messages: usize,
+    /// Messages dropped for being in an export merged before.
+    pub duplicates: usize,
+}
+
+/// Identifies a message across exports: its time, sender and text.
+///
+/// Times are compared to the minute, because Android exports drop the
+/// seconds iOS exports keep. Messages without a timestamp are compared by
+/// their raw date and time instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum MessageKey {
+    Timestamp(NaiveDateTime, String, String),
+    Raw(String, String, String, String),
+}
+
+impl MessageKey {
+    fn of(message: &Message) -> MessageKey {
+        let owner = message.owner.clone();
+        let text = message.text.clone();
+        match message.timestamp.and_then(|t| t.with_second(0)) {
+            Some(minute) => MessageKey::Timestamp(minute, owner, text),
+            None => MessageKey::Raw(message.date.clone(), message.hour.clone(), owner, text),
+        }
+    }
+}
+
+/// Merges overlapping exports of the same chat into one chronological list,
+/// dropping the messages found in more than one export.
+///
+/// A message sent several times in the same minute is kept as many times as
+/// the export containing it most often has it, so repeated messages within
+/// one export survive the merge.
+pub fn merge_exports(exports: Vec<Vec<Message>>) -> (Vec<Message>, MergeReport) {
+    let mut report = MergeReport {
+        exports: exports.len(),
+        ..Default::default()
+    };
+    let mut most_seen: HashMap<MessageKey, usize> = HashMap::new();
+    for export in &exports {
+        let mut seen: HashMap<MessageKey, usize> = HashMap::new();
+        for message in export {
+            *seen.entry(MessageKey::of(message)).or_insert(0) += 1;
+        }
+        for (key, count) in seen {
+            let most = most_seen.entry(key).or_insert(0);
+            *most = (*most).max(count);
+        }
+    }
+
+    let mut messages: Vec<Message> = exports.into_iter().flatten().collect();
+    messages.sort_by_key(|message| message.timestamp);
+    let mut kept: HashMap<MessageKey, usize> = HashMap::new();
+    messages.retain(|message| {
+        let key = MessageKey::of(message);
+        let allowed = most_seen.get(&key).copied().unwrap_or(0);
+        let count = kept.entry(key).or_insert(0);
+        if *count < allowed {
+            *count += 1;
+            true
+        } else {
+            report.duplicates += 1;
+            false
+        }
+    });
+    report.messages = messages.len();
+    (messages, report)
+}
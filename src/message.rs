@@ -1,46 +1,196 @@
+use crate::attachment::Attachment;
 use crate::errors::ParseError;
+use crate::link::extract_links;
+use crate::mention::extract_mentions;
+use crate::system::SystemEvent;
+use crate::utils::text_utils::{normalize_line, normalize_name};
+use crate::utils::time_utils::{DateOrder, DateOrderDetector, normalize_time, parse_timestamp};
+use chrono::NaiveDateTime;
 
 /// A WhatsApp message with date, time, sender, and content.
-#[derive(Debug)]
+///
+/// `date` is kept exactly as written in the export and `hour` is always the
+/// 24-hour `HH:MM` (or `HH:MM:SS`) form of the time, whatever clock the
+/// export was written with. `timestamp` holds both parsed together, and is
+/// `None` until the order of the date fields is known (see
+/// [`Message::resolve_timestamp`]) or when the date can't be understood.
+#[derive(Debug, Default)]
 pub struct Message {
     pub date: String,
     pub hour: String,
     pub owner: String,
     pub text: String,
+    pub timestamp: Option<NaiveDateTime>,
+    pub kind: MessageKind,
+    pub attachment: Option<Attachment>,
+    /// The message was deleted; `text` is empty.
+    pub deleted: bool,
+    /// The message was edited after being sent.
+    pub edited: bool,
+    /// Names and numbers mentioned with `@`, resolved to participant names
+    /// where known (see [`crate::mention::resolve_mentions`]).
+    pub mentions: Vec<String>,
+    /// Links shared in `text`, as written.
+    pub links: Vec<String>,
+}
+
+/// Texts WhatsApp leaves in place of a deleted message.
+const DELETED_MARKERS: &[&str] = &["This message was deleted", "You deleted this message"];
+
+/// Marker WhatsApp appends to the text of an edited message.
+const EDITED_MARKER: &str = "<This message was edited>";
+
+/// Whether a message was written by a participant or generated by WhatsApp.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MessageKind {
+    #[default]
+    User,
+    System(SystemEvent),
+}
+
+/// The flavor of a WhatsApp export, which decides how message headers look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// iOS exports: `[31/12/22, 21:41:05] owner: message`
+    Ios,
+    /// Android exports: `31/12/22, 21:41 - owner: message`
+    Android,
+}
+
+impl ExportFormat {
+    /// Detects the flavor of an export from the first line that is a valid
+    /// header in one of the known formats.
+    ///
+    /// Returns `None` if no line looks like a message header.
+    pub fn detect(content: &str) -> Option<ExportFormat> {
+        content.lines().find_map(|line| {
+            [ExportFormat::Ios, ExportFormat::Android]
+                .into_iter()
+                .find(|format| split_header(&normalize_line(line), *format).is_some())
+        })
+    }
 }
 
 impl Message {
-    /// Parses a line like `[date, time] owner: message` into a `Message`.
+    /// Parses a line like `[date, time] owner: message` or
+    /// `date, time - owner: message` into a `Message`.
     ///
     /// Returns `Err(ParseError::NoSplitter)` if the line is malformed.
     pub fn new(line: &str) -> Result<Self, ParseError> {
-        let line = line.trim();
-        if !line.starts_with('[') {
-            return Err(ParseError::NoSplitter);
-        }
-        let end_bracket = line.find(']').ok_or(ParseError::NoSplitter)?;
-        let datetime_str = &line[1..end_bracket];
-        let rest = line[end_bracket + 1..].trim();
-        let mut dt_parts = datetime_str.split(", ");
-        let date = dt_parts.next().ok_or(ParseError::NoSplitter)?.to_string();
-        let hour = dt_parts.next().ok_or(ParseError::NoSplitter)?.to_string();
-        let mut rest_parts = rest.splitn(2, ':');
-        let owner = rest_parts
-            .next()
-            .ok_or(ParseError::NoSplitter)?
-            .trim()
-            .to_string();
-        let text = rest_parts
-            .next()
-            .ok_or(ParseError::NoSplitter)?
-            .trim()
-            .to_string();
-        Ok(Message {
-            date,
+        let format = ExportFormat::detect(line).ok_or(ParseError::NoSplitter)?;
+        Message::parse(&normalize_line(line), format)
+    }
+
+    /// Parses a single header line written in the given export `format`.
+    /// The line is expected to have gone through
+    /// [`normalize_line`](crate::utils::text_utils::normalize_line).
+    ///
+    /// The timestamp is only filled in when the date alone tells the order of
+    /// its fields, e.g. `31/12/22`.
+    ///
+    /// System events are recognized either as a header without an `owner:`
+    /// part, or (as iOS writes them) as a text starting with a left-to-right
+    /// mark. For those, `owner` is the participant who caused the event, or
+    /// empty for iOS notices that aren't recognized.
+    ///
+    /// Returns `Err(ParseError::NoSplitter)` if the line is not a header.
+    pub fn parse(line: &str, format: ExportFormat) -> Result<Self, ParseError> {
+        let (date, hour, rest) = split_header(line, format).ok_or(ParseError::NoSplitter)?;
+        let (owner, text, kind) = match SystemEvent::parse(rest) {
+            Some(event) if event.participants().iter().all(|name| !name.contains(':')) => {
+                let actor = event.actor().unwrap_or_default().to_string();
+                (actor, rest, MessageKind::System(event))
+            }
+            _ => match rest.split_once(':') {
+                Some((owner, text)) => {
+                    let text = text.trim();
+                    match text.strip_prefix('\u{200E}').and_then(SystemEvent::parse) {
+                        Some(event) => {
+                            let actor = event.actor().unwrap_or(owner).to_string();
+                            (actor, text, MessageKind::System(event))
+                        }
+                        None if format == ExportFormat::Ios && is_ios_notice(text) => {
+                            let notice = text.trim_start_matches('\u{200E}').trim();
+                            let event = SystemEvent::Other(notice.to_string());
+                            (String::new(), text, MessageKind::System(event))
+                        }
+                        None => (owner.to_string(), text, MessageKind::User),
+                    }
+                }
+                None => (
+                    String::new(),
+                    rest,
+                    MessageKind::System(SystemEvent::Other(rest.to_string())),
+                ),
+            },
+        };
+        let hour = normalize_time(hour).ok_or(ParseError::NoSplitter)?;
+        let text = text.trim_start_matches('\u{200E}').trim();
+        let deleted =
+            kind == MessageKind::User && DELETED_MARKERS.contains(&text.trim_end_matches('.'));
+        let text = if deleted { "" } else { text };
+        let (text, attachment) = match &kind {
+            MessageKind::User => match Attachment::parse(text) {
+                Some((attachment, caption)) => (caption, Some(attachment)),
+                None => (text.to_string(), None),
+            },
+            MessageKind::System(_) => (text.to_string(), None),
+        };
+        let mut detector = DateOrderDetector::default();
+        detector.observe(date);
+        let mut message = Message {
+            date: date.to_string(),
+            timestamp: detector
+                .finish()
+                .ok()
+                .and_then(|order| parse_timestamp(date, &hour, order)),
             hour,
-            owner,
+            owner: normalize_name(&owner),
             text,
-        })
+            kind,
+            attachment,
+            deleted,
+            edited: false,
+            mentions: Vec::new(),
+            links: Vec::new(),
+        };
+        message.finish();
+        Ok(message)
+    }
+
+    /// Returns true if the message was written by a participant rather than
+    /// generated by WhatsApp.
+    pub fn is_user(&self) -> bool {
+        self.kind == MessageKind::User
+    }
+
+    /// Parses `timestamp` from `date` and `hour` using the given date order.
+    pub fn resolve_timestamp(&mut self, order: DateOrder) {
+        self.timestamp = parse_timestamp(&self.date, &self.hour, order);
+    }
+
+    /// Appends a continuation line of a multi-line message to `text`.
+    /// Call [`Message::finish`] once the last line has been appended.
+    pub fn append_line(&mut self, line: &str) {
+        self.text.push('\n');
+        self.text.push_str(line.trim_end());
+    }
+
+    /// Tidies up `text` once the whole message is known: trims trailing
+    /// blank lines, moves a trailing edited marker into `edited` and reads
+    /// the mentions, as written, into `mentions` and the links into `links`.
+    pub fn finish(&mut self) {
+        let text = self.text.trim_end();
+        if let Some(text) = text.strip_suffix(EDITED_MARKER) {
+            self.edited = true;
+            self.text = text.trim_end_matches('\u{200E}').trim_end().to_string();
+        } else {
+            self.text.truncate(text.len());
+        }
+        if self.is_user() {
+            self.mentions = extract_mentions(&self.text);
+            self.links = extract_links(&self.text);
+        }
     }
 
     /// Prints the message to the screen
@@ -52,6 +202,58 @@ impl Message {
     }
 }
 
+/// Splits a header line into its date, time and the remaining `owner: message` part.
+///
+/// Returns `None` if the line does not start with a header in the given format.
+fn split_header(line: &str, format: ExportFormat) -> Option<(&str, &str, &str)> {
+    let line = line.trim();
+    let (datetime, rest) = match format {
+        ExportFormat::Ios => line.strip_prefix('[')?.split_once(']')?,
+        ExportFormat::Android => line.split_once(" - ")?,
+    };
+    let (date, hour) = datetime.split_once(", ")?;
+    if !starts_with_digit(date) || normalize_time(hour).is_none() {
+        return None;
+    }
+    Some((date, hour, rest.trim()))
+}
+
+/// Checks whether a line starts with a date followed by a comma the way a
+/// header does, e.g. `[13/5/22, ` or `2022-05-13, `, whether or not the rest
+/// of the header can be read.
+pub fn looks_like_header(line: &str, format: ExportFormat) -> bool {
+    let line = match format {
+        ExportFormat::Ios => match line.trim().strip_prefix('[') {
+            Some(line) => line,
+            None => return false,
+        },
+        ExportFormat::Android => line.trim(),
+    };
+    let date_len = line
+        .find(|c: char| !c.is_ascii_digit() && !matches!(c, '/' | '.' | '-'))
+        .unwrap_or(line.len());
+    let date = &line[..date_len];
+    starts_with_digit(date)
+        && date.chars().filter(|c| !c.is_ascii_digit()).count() == 2
+        && line[date_len..].starts_with(',')
+}
+
+/// Checks whether an iOS message text is a notice written by WhatsApp, which
+/// starts with a left-to-right mark, rather than an attachment placeholder or
+/// a deleted message that carries the same mark.
+fn is_ios_notice(text: &str) -> bool {
+    let Some(text) = text.strip_prefix('\u{200E}') else {
+        return false;
+    };
+    let text = text.trim();
+    !DELETED_MARKERS.contains(&text.trim_end_matches('.')) && Attachment::parse(text).is_none()
+}
+
+/// Checks that a header field looks like a date or a time rather than free text.
+fn starts_with_digit(field: &str) -> bool {
+    field.chars().next().is_some_and(|c| c.is_ascii_digit())
+}
+
 impl PartialEq for Message {
     /// Implements the Eq trait for the message. Implemented for testing purposes
     ///
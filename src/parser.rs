@@ -1,39 +1,471 @@
+use crate::archive::{self, MediaIndex};
+use crate::diagnostics::{ParseReport, RejectReason, RejectedLine};
 use crate::errors::ParseError;
-use crate::message::Message;
-use std::fs;
+use crate::input;
+use crate::mention::resolve_mentions;
+use crate::message::{ExportFormat, Message, looks_like_header};
+use crate::source::ChatSource;
+use crate::utils::text_utils::normalize_line;
+use crate::utils::time_utils::{DateOrder, DateOrderDetector};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, Cursor, Read};
 use std::path::Path;
-use std::str::Split;
+
+/// Most messages a [`MessageStream`] holds back while their dates don't tell
+/// whether the day or the month comes first.
+pub const MAX_HELD_BACK: usize = 10_000;
 
 pub struct Parser<'a> {
     filepath: &'a Path,
+    date_order: Option<DateOrder>,
+    strict: bool,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(filepath: &'a Path) -> Self {
-        Parser { filepath }
-    }
-    pub fn read_file(&self) -> String {
-        let contents =
-            fs::read_to_string(&self.filepath).expect("Should have been able to read the file");
-        contents
-    }
-    pub fn parse(&self) -> Result<Vec<Message>, ParseError> {
-        let content: String = self.read_file();
-        let file_rows: Split<'_, &str> = content.split("\n");
-
-        let mut messages_array: Vec<Message> = Vec::new();
-        for row in file_rows {
-            let message = Message::new(row);
-            match message {
-                Ok(message) => {
-                    messages_array.push(message);
+        Parser {
+            filepath,
+            date_order: None,
+            strict: false,
+        }
+    }
+
+    /// Uses the given date order instead of detecting it from the file.
+    pub fn with_date_order(mut self, date_order: DateOrder) -> Self {
+        self.date_order = Some(date_order);
+        self
+    }
+
+    /// Fails with `ParseError::UnparseableLine` on the first line that can't
+    /// be parsed, or `ParseError::InvalidEncoding` on the first one that can't
+    /// be decoded, instead of skipping it and listing it in the report.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Reads the whole chat text, see [`Parser::messages`] for the inputs
+    /// it is read from.
+    ///
+    /// Returns `Err(ParseError::InvalidEncoding)` if the text can't be decoded.
+    pub fn read_file(&self) -> Result<String, ParseError> {
+        let mut reader = self.open_chat()?;
+        let mut content = String::new();
+        let mut line_number = 1;
+        while input::read_line(&mut reader, &mut content, line_number)? > 0 {
+            line_number += 1;
+        }
+        Ok(content)
+    }
+
+    /// Opens the chat text for buffered reading, either from a plain text
+    /// export or from the chat file inside an exported `.zip` archive.
+    ///
+    /// The path may be `-` to read standard input, and the input may be gzip
+    /// or zstd compressed. The chat inside an archive is decompressed into
+    /// memory first, plain text exports are read as they are parsed.
+    ///
+    /// The text is decoded from UTF-8 or UTF-16, told by its byte order mark.
+    fn open_chat(&self) -> Result<Box<dyn BufRead>, ParseError> {
+        if !input::is_stdin(self.filepath) && archive::is_zip(self.filepath)? {
+            let chat = archive::read_chat(File::open(self.filepath)?)?;
+            return input::decode(Box::new(Cursor::new(chat)));
+        }
+        let mut reader = input::open(self.filepath)?;
+        if archive::starts_with_zip_magic(reader.fill_buf()?) {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            let chat = archive::read_chat(Cursor::new(bytes))?;
+            return input::decode(Box::new(Cursor::new(chat)));
+        }
+        input::decode(reader)
+    }
+
+    /// Opens the export and returns an iterator that parses it line by line,
+    /// for going through very large chats without keeping them in memory.
+    /// Only the chat text of a `.zip` archive is held in memory, as it has to
+    /// be decompressed out of the archive first.
+    ///
+    /// The path may point at the exported `.txt` file or at the `.zip`
+    /// archive WhatsApp produces when exporting a chat, either of them
+    /// possibly gzip or zstd compressed, or be `-` to read standard input.
+    ///
+    /// Unless given, the date order is detected by reading the dates of the
+    /// export before parsing it, so that no message has to be held back.
+    /// Standard input can't be read twice and has its date order detected
+    /// while it is parsed instead, see [`MessageStream`].
+    pub fn messages(&self) -> Result<MessageStream<Box<dyn BufRead>>, ParseError> {
+        let mut stream = MessageStream::new(self.open_chat()?);
+        if let Some(date_order) = self.date_order {
+            stream = stream.with_date_order(date_order);
+        } else if !input::is_stdin(self.filepath) {
+            stream = match detect_date_order(self.open_chat()?)? {
+                Some(date_order) => stream.with_date_order(date_order),
+                None => stream.without_date_order(),
+            };
+        }
+        if self.strict {
+            stream = stream.strict();
+        }
+        Ok(stream)
+    }
+}
+
+impl ChatSource for Parser<'_> {
+    /// Parses the export into messages, together with a report of what was
+    /// made of each line. Mentions are resolved against all the senders in
+    /// the export.
+    ///
+    /// See [`MessageStream`] for how the export is read.
+    fn parse_with_report(&self) -> Result<(Vec<Message>, ParseReport), ParseError> {
+        let mut stream = self.messages()?.hold_back_all();
+        let mut messages = stream
+            .by_ref()
+            .collect::<Result<Vec<Message>, ParseError>>()?;
+        resolve_mentions(&mut messages);
+        Ok((messages, stream.into_report()))
+    }
+
+    /// Yields the messages as the export is parsed, without resolving
+    /// mentions, which needs every sender of the chat.
+    fn stream(
+        &self,
+    ) -> Result<Box<dyn Iterator<Item = Result<Message, ParseError>> + '_>, ParseError> {
+        Ok(Box::new(self.messages()?))
+    }
+
+    /// Runs through the whole export without keeping the messages.
+    fn check(&self) -> Result<ParseReport, ParseError> {
+        let mut stream = self.messages()?;
+        for message in stream.by_ref() {
+            message?;
+        }
+        Ok(stream.into_report())
+    }
+
+    /// Lists the media files bundled with the export. Plain text exports have
+    /// no bundled media and give an empty index, and so does standard input,
+    /// which can't be read a second time.
+    fn media_index(&self) -> Result<MediaIndex, ParseError> {
+        if input::is_stdin(self.filepath) {
+            return Ok(MediaIndex::default());
+        }
+        if archive::is_zip(self.filepath)? {
+            return archive::index_media(File::open(self.filepath)?);
+        }
+        let mut reader = input::open(self.filepath)?;
+        if archive::starts_with_zip_magic(reader.fill_buf()?) {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            return archive::index_media(Cursor::new(bytes));
+        }
+        Ok(MediaIndex::default())
+    }
+}
+
+/// Reads the dates of the message headers in `reader` to tell the order of
+/// their fields, stopping at the first date that does.
+///
+/// Returns `Ok(None)` if no date tells whether the day or the month comes
+/// first.
+fn detect_date_order(mut reader: impl BufRead) -> Result<Option<DateOrder>, ParseError> {
+    let mut detector = DateOrderDetector::default();
+    let mut format = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let (read, valid) = input::read_line_lossy(&mut reader, &mut line)?;
+        if read == 0 {
+            break;
+        }
+        if !valid {
+            continue;
+        }
+        let row = normalize_line(line.trim_end_matches(['\n', '\r']));
+        if format.is_none() {
+            format = ExportFormat::detect(&row);
+        }
+        if let Some(Ok(message)) = format.map(|format| Message::parse(&row, format)) {
+            detector.observe(&message.date);
+            if let Some(date_order) = detector.settled()? {
+                return Ok(Some(date_order));
+            }
+        }
+    }
+    match detector.finish() {
+        Ok(date_order) => Ok(Some(date_order)),
+        Err(ParseError::AmbiguousDateOrder) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Parses messages lazily out of a buffered reader, one line at a time.
+///
+/// Each line is normalized first, dropping the byte order mark and
+/// directional marks in front of it and replacing no-break spaces, so
+/// headers are found and sender names compare equal.
+///
+/// The export flavor (iOS or Android) is detected from the first header.
+/// Lines that don't start a new header are continuation lines of a
+/// multi-line message and get appended to the previous message, so a message
+/// is only yielded once the next header (or the end of the input) is read.
+/// Lines found before the first header, lines that start with a date but
+/// aren't a readable header, and lines that aren't valid text are skipped and
+/// listed in the report's rejects, or end the stream with an error in
+/// [`MessageStream::strict`] mode.
+///
+/// Unless set with [`MessageStream::with_date_order`], the order of the date
+/// fields is detected from the dates read so far. Messages whose date alone
+/// doesn't tell are held back until a later date does, up to
+/// [`MAX_HELD_BACK`] of them. If no date in the input, or among that many
+/// messages, tells, the messages are yielded without a timestamp and counted
+/// in the report as undated.
+pub struct MessageStream<R> {
+    reader: R,
+    line: String,
+    format: Option<ExportFormat>,
+    date_order: Option<DateOrder>,
+    detect_date_order: bool,
+    detector: DateOrderDetector,
+    max_held_back: usize,
+    strict: bool,
+    /// The last message read, which further lines may still be appended to.
+    pending: Option<Message>,
+    /// Complete messages waiting to be yielded.
+    ready: VecDeque<Message>,
+    report: ParseReport,
+    done: bool,
+}
+
+impl<R: BufRead> MessageStream<R> {
+    pub fn new(reader: R) -> Self {
+        MessageStream {
+            reader,
+            line: String::new(),
+            format: None,
+            date_order: None,
+            detect_date_order: true,
+            detector: DateOrderDetector::default(),
+            max_held_back: MAX_HELD_BACK,
+            strict: false,
+            pending: None,
+            ready: VecDeque::new(),
+            report: ParseReport::default(),
+            done: false,
+        }
+    }
+
+    /// Uses the given date order instead of detecting it from the input.
+    pub fn with_date_order(mut self, date_order: DateOrder) -> Self {
+        self.date_order = Some(date_order);
+        self.detect_date_order = false;
+        self
+    }
+
+    /// Yields messages without a timestamp right away, instead of holding
+    /// them back to detect the date order, for input known not to tell it.
+    pub fn without_date_order(mut self) -> Self {
+        self.date_order = None;
+        self.detect_date_order = false;
+        self
+    }
+
+    /// Holds back as many messages as it takes to detect the date order,
+    /// for callers that keep every message anyway.
+    pub fn hold_back_all(mut self) -> Self {
+        self.max_held_back = usize::MAX;
+        self
+    }
+
+    /// Ends the stream with `ParseError::UnparseableLine` on the first line
+    /// that can't be parsed, or `ParseError::InvalidEncoding` on the first one
+    /// that can't be decoded, instead of skipping it and listing it in the report.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Returns what was made of the lines read so far.
+    pub fn report(&self) -> &ParseReport {
+        &self.report
+    }
+
+    pub fn into_report(self) -> ParseReport {
+        self.report
+    }
+
+    /// Reads and handles the next line. Returns `Ok(false)` at the end of the input.
+    fn read_line(&mut self) -> Result<bool, ParseError> {
+        self.line.clear();
+        let line_number = self.report.lines_read + 1;
+        let (read, valid) = if self.strict {
+            let read = input::read_line(&mut self.reader, &mut self.line, line_number)?;
+            (read, true)
+        } else {
+            input::read_line_lossy(&mut self.reader, &mut self.line)?
+        };
+        if read == 0 {
+            return Ok(false);
+        }
+        self.report.lines_read = line_number;
+        let raw_row = self.line.trim_end_matches(['\n', '\r']);
+        if !valid {
+            self.report.rejects.push(RejectedLine {
+                line_number,
+                content: raw_row.to_string(),
+                reason: RejectReason::InvalidEncoding,
+            });
+            return Ok(true);
+        }
+        let row = normalize_line(raw_row);
+        if self.format.is_none() {
+            self.format = ExportFormat::detect(&row);
+        }
+        let message = match self.format {
+            Some(format) => Message::parse(&row, format),
+            None => Err(ParseError::NoSplitter),
+        };
+        let reason = match message {
+            Ok(message) => {
+                if message.is_user() {
+                    self.report.messages += 1;
+                } else {
+                    self.report.system_events += 1;
                 }
-                Err(_) => {
-                    continue;
+                if let Some(previous) = self.pending.replace(message) {
+                    self.push_ready(previous)?;
                 }
+                return Ok(true);
             }
+            Err(ParseError::NoSplitter) => {
+                if row.trim().is_empty() {
+                    self.report.blank_lines += 1;
+                }
+                if self
+                    .format
+                    .is_some_and(|format| looks_like_header(&row, format))
+                {
+                    RejectReason::MalformedHeader
+                } else if let Some(previous) = self.pending.as_mut() {
+                    previous.append_line(&row);
+                    if !row.trim().is_empty() {
+                        self.report.continuations += 1;
+                    }
+                    return Ok(true);
+                } else if row.trim().is_empty() {
+                    return Ok(true);
+                } else {
+                    RejectReason::BeforeFirstMessage
+                }
+            }
+            Err(err) => return Err(err),
+        };
+        if self.strict {
+            return Err(ParseError::UnparseableLine {
+                line_number,
+                reason,
+            });
         }
+        self.report.rejects.push(RejectedLine {
+            line_number,
+            content: raw_row.to_string(),
+            reason,
+        });
+        Ok(true)
+    }
 
-        Ok(messages_array)
+    /// Queues a message no more lines will be appended to, learning the date
+    /// order from its date. Once too many messages are waiting on the date
+    /// order, it is decided from the dates seen so far.
+    fn push_ready(&mut self, mut message: Message) -> Result<(), ParseError> {
+        message.finish();
+        if self.detect_date_order {
+            self.detector.observe(&message.date);
+            if let Some(date_order) = self.detector.settled()? {
+                self.date_order = Some(date_order);
+            }
+        }
+        self.ready.push_back(message);
+        if self.is_detecting() && self.ready.len() > self.max_held_back {
+            self.decide_date_order()?;
+        }
+        Ok(())
+    }
+
+    /// Checks whether messages are still held back for the date order.
+    fn is_detecting(&self) -> bool {
+        self.detect_date_order && self.date_order.is_none()
+    }
+
+    /// Decides the date order from the dates seen so far. If none of them
+    /// tells, the messages are yielded without a timestamp from then on.
+    fn decide_date_order(&mut self) -> Result<(), ParseError> {
+        match self.detector.finish() {
+            Ok(date_order) => self.date_order = Some(date_order),
+            Err(ParseError::AmbiguousDateOrder) => {}
+            Err(err) => return Err(err),
+        }
+        self.detect_date_order = false;
+        Ok(())
+    }
+
+    /// Handles the end of the input, deciding the date order from all the
+    /// dates seen if none of them settled it.
+    fn finish(&mut self) -> Result<(), ParseError> {
+        if let Some(message) = self.pending.take() {
+            self.push_ready(message)?;
+        }
+        if self.is_detecting() && !self.ready.is_empty() {
+            self.decide_date_order()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the next queued message once its timestamp can be resolved,
+    /// or once it's known that it can't be.
+    fn pop_ready(&mut self) -> Option<Message> {
+        let front = self.ready.front()?;
+        let message = match self.date_order {
+            Some(date_order) => {
+                let mut message = self.ready.pop_front()?;
+                message.resolve_timestamp(date_order);
+                message
+            }
+            None if !self.detect_date_order || front.timestamp.is_some() => {
+                self.ready.pop_front()?
+            }
+            None => return None,
+        };
+        if message.timestamp.is_none() {
+            self.report.undated += 1;
+        }
+        Some(message)
+    }
+}
+
+impl<R: BufRead> Iterator for MessageStream<R> {
+    type Item = Result<Message, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(message) = self.pop_ready() {
+                return Some(Ok(message));
+            }
+            if self.done {
+                return None;
+            }
+            let step = match self.read_line() {
+                Ok(true) => continue,
+                Ok(false) => self.finish(),
+                Err(err) => Err(err),
+            };
+            self.done = true;
+            if let Err(err) = step {
+                self.pending = None;
+                self.ready.clear();
+                return Some(Err(err));
+            }
+        }
     }
 }
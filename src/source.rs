@@ -0,0 +1,40 @@
+use crate::archive::MediaIndex;
+use crate::diagnostics::ParseReport;
+use crate::errors::ParseError;
+use crate::message::Message;
+
+/// Anything chat messages can be read from, such as an export in one of the
+/// supported formats.
+///
+/// The messages a source yields work with every function in `stats` and
+/// with the html dashboard. Implement it to add an input format of your own.
+pub trait ChatSource {
+    /// Parses the source into messages, together with a report of what was
+    /// made of its input.
+    fn parse_with_report(&self) -> Result<(Vec<Message>, ParseReport), ParseError>;
+
+    /// Yields the messages of the source one at a time, for stats that go
+    /// through them once. Sources that can't be read incrementally parse
+    /// everything up front.
+    fn stream(
+        &self,
+    ) -> Result<Box<dyn Iterator<Item = Result<Message, ParseError>> + '_>, ParseError> {
+        Ok(Box::new(self.parse()?.into_iter().map(Ok)))
+    }
+
+    /// Parses the source into messages.
+    fn parse(&self) -> Result<Vec<Message>, ParseError> {
+        self.parse_with_report().map(|(messages, _)| messages)
+    }
+
+    /// Reads the whole source for its report, without returning the messages.
+    fn check(&self) -> Result<ParseReport, ParseError> {
+        self.parse_with_report().map(|(_, report)| report)
+    }
+
+    /// Lists the media files shipped with the source. Sources without bundled
+    /// media give an empty index.
+    fn media_index(&self) -> Result<MediaIndex, ParseError> {
+        Ok(MediaIndex::default())
+    }
+}
@@ -1,80 +1,196 @@
-use crate::{errors::DatabaseError, message::Message, utils::time_utils::get_hour};
+use crate::{
+    archive::MediaIndex,
+    emoji::{extract_emoji, strip_emoji},
+    errors::DatabaseError,
+    link::{link_domain, strip_links},
+    mention::strip_mentions,
+    message::Message,
+    tokenizer::Tokenizer,
+    utils::time_utils::get_hour,
+};
+use chrono::{Datelike, NaiveDateTime};
+use std::borrow::Borrow;
 use std::collections::HashMap;
 
-/// Counts how many times a specific word appears per user across all messages.
-/// The comparison is case-insensitive and matches whole words only.
+/// Iterates over the messages written by participants, skipping the system
+/// events every stat leaves out.
+///
+/// Stats that only need to look at each message once take the messages as
+/// any iterator, a slice or the messages of a [`crate::parser::MessageStream`]
+/// alike, so they can be computed while the chat is being parsed.
+pub fn user_messages<M: Borrow<Message>>(
+    messages: impl IntoIterator<Item = M>,
+) -> impl Iterator<Item = M> {
+    messages
+        .into_iter()
+        .filter(|message| message.borrow().is_user())
+}
+
+/// Returns the text of a message with its mentions, links and emoji left
+/// out, as the word stats count it.
+fn word_text(message: &Message) -> String {
+    strip_emoji(&strip_links(&strip_mentions(&message.text)))
+}
+
+/// Returns the words of a message as the given tokenizer splits them.
+fn message_words(message: &Message, tokenizer: &Tokenizer) -> Vec<String> {
+    tokenizer.tokens(&word_text(message))
+}
+
+/// Counts how many times the words of `target` appear in a row in `words`.
+fn count_occurrences(words: &[String], target: &[String]) -> usize {
+    if target.is_empty() {
+        return 0;
+    }
+    words.windows(target.len()).filter(|w| *w == target).count()
+}
+
+/// Counts how many messages of each user contain a specific word.
+/// The target is split and folded by the tokenizer like the messages are,
+/// and matches whole words only.
 pub fn count_word_per_user(
-    messages: &[Message],
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
     target: &str,
+    tokenizer: &Tokenizer,
 ) -> Result<HashMap<String, i64>, DatabaseError> {
     let mut counts: HashMap<String, i64> = HashMap::new();
-    let target_lower = target.to_lowercase();
-    for message in messages {
-        if message
-            .text
-            .to_lowercase()
-            .split_whitespace()
-            .any(|word| word == target_lower)
-        {
+    let target = tokenizer.tokens(target);
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        if count_occurrences(&message_words(message, tokenizer), &target) > 0 {
             *counts.entry(message.owner.clone()).or_insert(0) += 1;
         }
     }
     Ok(counts)
 }
 
-/// Counts how many times a specific phrase appears per user across all messages.
-/// The comparison is case-insensitive and matches substrings.
+/// Counts how many messages of each user contain a specific phrase.
+/// The phrase is split and folded by the tokenizer like the messages are,
+/// and matches whole words in a row that aren't split by punctuation, so
+/// "cat" doesn't match "concatenate".
 pub fn count_phrase_per_user(
-    messages: &[Message],
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
     target: &str,
+    tokenizer: &Tokenizer,
 ) -> Result<HashMap<String, i64>, DatabaseError> {
     let mut counts: HashMap<String, i64> = HashMap::new();
-    let target_lower = target.to_lowercase();
-    for message in messages {
-        if message.text.to_lowercase().contains(&target_lower) {
+    let target = tokenizer.tokens(target);
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        let said = tokenizer
+            .phrases(&word_text(message))
+            .iter()
+            .any(|phrase| count_occurrences(phrase, &target) > 0);
+        if said {
             *counts.entry(message.owner.clone()).or_insert(0) += 1;
         }
     }
     Ok(counts)
 }
 
-/// Builds a frequency map of all words across all messages.
-/// Words are converted to lowercase to normalize the results.
-pub fn total_word_count(messages: &[Message]) -> Result<HashMap<String, i64>, DatabaseError> {
+/// Builds a frequency map of all words across all messages, as split and
+/// folded by the tokenizer. Mentions, links, emoji and the tokenizer's
+/// stopwords are left out.
+pub fn total_word_count(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+    tokenizer: &Tokenizer,
+) -> Result<HashMap<String, i64>, DatabaseError> {
     let mut counts: HashMap<String, i64> = HashMap::new();
-    for message in messages {
-        for word in message.text.split_whitespace() {
-            *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        for word in message_words(message, tokenizer) {
+            if tokenizer.is_stopword(&word) {
+                continue;
+            }
+            *counts.entry(word).or_insert(0) += 1;
         }
     }
     Ok(counts)
 }
 
 /// Counts how many times a specific word appears in the entire message set.
-/// The comparison is case-insensitive and matches whole words only.
-pub fn extract_word_count(messages: &[Message], target: &str) -> Result<usize, DatabaseError> {
-    let mut count = 0;
-    for message in messages {
-        for word in message.text.split_whitespace() {
-            if word.eq_ignore_ascii_case(target) {
-                count += 1;
-            }
+/// The target is split and folded by the tokenizer like the messages are,
+/// and matches whole words only.
+pub fn extract_word_count(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+    target: &str,
+    tokenizer: &Tokenizer,
+) -> Result<usize, DatabaseError> {
+    let target = tokenizer.tokens(target);
+    let count = user_messages(messages)
+        .map(|message| count_occurrences(&message_words(message.borrow(), tokenizer), &target))
+        .sum();
+    Ok(count)
+}
+
+/// Returns the `n`-word phrases of a message, its words joined by spaces.
+/// Phrases don't run across punctuation, and those starting or ending with
+/// a stopword, such as "of the" or "in the park", are left out.
+fn message_ngrams(message: &Message, n: usize, tokenizer: &Tokenizer) -> Vec<String> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut ngrams = Vec::new();
+    for phrase in tokenizer.phrases(&word_text(message)) {
+        ngrams.extend(
+            phrase
+                .windows(n)
+                .filter(|words| {
+                    !tokenizer.is_stopword(&words[0]) && !tokenizer.is_stopword(&words[n - 1])
+                })
+                .map(|words| words.join(" ")),
+        );
+    }
+    ngrams
+}
+
+/// Counts the most frequent `n`-word phrases across all messages, as split
+/// and folded by the tokenizer. Phrases don't run across messages or
+/// punctuation, and those starting or ending with one of the tokenizer's
+/// stopwords are left out.
+pub fn ngram_count(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+    n: usize,
+    tokenizer: &Tokenizer,
+) -> Result<HashMap<String, i64>, DatabaseError> {
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        for ngram in message_ngrams(message, n, tokenizer) {
+            *counts.entry(ngram).or_insert(0) += 1;
         }
     }
-    Ok(count)
+    Ok(counts)
+}
+
+/// Counts the `n`-word phrases each user wrote, as [`ngram_count`] does.
+/// Returns a map of username to a map of phrase to count.
+pub fn ngrams_per_user(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+    n: usize,
+    tokenizer: &Tokenizer,
+) -> Result<HashMap<String, HashMap<String, i64>>, DatabaseError> {
+    let mut counts: HashMap<String, HashMap<String, i64>> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        for ngram in message_ngrams(message, n, tokenizer) {
+            let user_counts = counts.entry(message.owner.clone()).or_default();
+            *user_counts.entry(ngram).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
 }
 
 /// Finds the top speaker for each hour by counting user messages per hour.
 /// Returns a map of hour (HH format) to the username who spoke the most in that hour.
 pub fn top_speaker_per_hour(
-    messages: &[Message],
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
 ) -> Result<HashMap<String, String>, DatabaseError> {
     let mut hour_to_user_counts: HashMap<String, HashMap<String, i64>> = HashMap::new();
-    for message in messages {
+    for message in user_messages(messages) {
+        let message = message.borrow();
         if let Some(hour) = message.hour.split(':').next() {
-            let user_counts = hour_to_user_counts
-                .entry(hour.to_lowercase())
-                .or_insert_with(HashMap::new);
+            let user_counts = hour_to_user_counts.entry(hour.to_lowercase()).or_default();
             *user_counts.entry(message.owner.clone()).or_insert(0) += 1;
         }
     }
@@ -88,20 +204,24 @@ pub fn top_speaker_per_hour(
 }
 
 /// Counts the total number of words sent across all messages in the dataset.
-/// Uses whitespace splitting to account for multiple spaces.
-pub fn words_sent(messages: &[Message]) -> Result<usize, DatabaseError> {
-    let total = messages
-        .iter()
-        .map(|m| m.text.split_whitespace().count())
+pub fn words_sent(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<usize, DatabaseError> {
+    let tokenizer = Tokenizer::new();
+    let total = user_messages(messages)
+        .map(|m| message_words(m.borrow(), &tokenizer).len())
         .sum();
     Ok(total)
 }
 
 /// Finds the hour of the day with the highest number of messages sent.
 /// Returns the hour in lowercase string form (e.g. "14" for 2 PM).
-pub fn most_active_hour(messages: &[Message]) -> Result<String, DatabaseError> {
+pub fn most_active_hour(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<String, DatabaseError> {
     let mut counts: HashMap<String, usize> = HashMap::new();
-    for message in messages {
+    for message in user_messages(messages) {
+        let message = message.borrow();
         let hour = get_hour(&message.hour);
         *counts.entry(hour.to_lowercase()).or_insert(0) += 1;
     }
@@ -114,11 +234,13 @@ pub fn most_active_hour(messages: &[Message]) -> Result<String, DatabaseError> {
 
 /// Returns the length (in words) of the single longest message in the dataset.
 /// If no messages are provided, returns 0.
-pub fn longest_message_length(messages: &[Message]) -> Result<usize, DatabaseError> {
-    if let Some((_, word_count)) = messages
-        .iter()
-        .map(|m| (m, m.text.split_whitespace().count()))
-        .max_by_key(|&(_, count)| count)
+pub fn longest_message_length(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<usize, DatabaseError> {
+    let tokenizer = Tokenizer::new();
+    if let Some(word_count) = user_messages(messages)
+        .map(|m| message_words(m.borrow(), &tokenizer).len())
+        .max()
     {
         Ok(word_count)
     } else {
@@ -129,36 +251,346 @@ pub fn longest_message_length(messages: &[Message]) -> Result<usize, DatabaseErr
 /// Computes the average number of words per message across all users.
 /// Returns 0.0 if there are no messages.
 pub fn average_words_per_message(messages: &[Message]) -> Result<f64, DatabaseError> {
-    if messages.is_empty() {
+    let message_count = user_messages(messages).count();
+    if message_count == 0 {
         return Ok(0.0);
     }
-    let total_words: usize = messages
-        .iter()
-        .map(|m| m.text.split_whitespace().count())
+    let tokenizer = Tokenizer::new();
+    let total_words: usize = user_messages(messages)
+        .map(|m| message_words(m, &tokenizer).len())
         .sum();
-    Ok(total_words as f64 / messages.len() as f64)
+    Ok(total_words as f64 / message_count as f64)
 }
 
 /// Computes the average number of messages sent per user across all messages.
 /// Returns 0.0 if no messages are provided.
-pub fn average_messages_per_user(messages: &[Message]) -> Result<f64, DatabaseError> {
-    if messages.is_empty() {
-        return Ok(0.0);
+pub fn average_messages_per_user(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<f64, DatabaseError> {
+    let mut user_counts: HashMap<String, usize> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        *user_counts.entry(message.owner.clone()).or_insert(0) += 1;
     }
-    let mut user_counts: HashMap<&str, usize> = HashMap::new();
-    for message in messages {
-        *user_counts.entry(&message.owner).or_insert(0) += 1;
+    if user_counts.is_empty() {
+        return Ok(0.0);
     }
-    let total_messages = messages.len();
+    let total_messages: usize = user_counts.values().sum();
     let unique_users = user_counts.len();
     Ok(total_messages as f64 / unique_users as f64)
 }
 
 // Counts the total number of messages sent by each user.
-pub fn messages_per_user(messages: &[Message]) -> Result<HashMap<String, i64>, DatabaseError> {
+pub fn messages_per_user(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, i64>, DatabaseError> {
     let mut counts: HashMap<String, i64> = HashMap::new();
-    for message in messages {
+    for message in user_messages(messages) {
+        let message = message.borrow();
         *counts.entry(message.owner.clone()).or_insert(0) += 1;
     }
     Ok(counts)
 }
+
+/// Counts messages per period, using `period` to turn a timestamp into its key.
+/// Messages without a parsed timestamp are skipped.
+fn messages_per_period(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+    period: impl Fn(&NaiveDateTime) -> String,
+) -> HashMap<String, i64> {
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    for message in user_messages(messages) {
+        if let Some(timestamp) = &message.borrow().timestamp {
+            *counts.entry(period(timestamp)).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Counts the messages sent on each day, keyed as `YYYY-MM-DD`.
+pub fn messages_per_day(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, i64>, DatabaseError> {
+    Ok(messages_per_period(messages, |t| {
+        t.format("%Y-%m-%d").to_string()
+    }))
+}
+
+/// Counts the messages sent on each day of the week, keyed by its English name.
+pub fn messages_per_weekday(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, i64>, DatabaseError> {
+    Ok(messages_per_period(messages, |t| {
+        t.format("%A").to_string()
+    }))
+}
+
+/// Counts the messages sent in each month, keyed as `YYYY-MM`.
+pub fn messages_per_month(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, i64>, DatabaseError> {
+    Ok(messages_per_period(messages, |t| {
+        t.format("%Y-%m").to_string()
+    }))
+}
+
+/// Counts the messages sent in each year, keyed as `YYYY`.
+pub fn messages_per_year(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, i64>, DatabaseError> {
+    Ok(messages_per_period(messages, |t| t.year().to_string()))
+}
+
+/// Counts the attachments each user shared, broken down by kind.
+/// Returns a map of username to a map of attachment kind (e.g. "image") to count.
+pub fn media_per_user(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, HashMap<String, i64>>, DatabaseError> {
+    let mut counts: HashMap<String, HashMap<String, i64>> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        if let Some(attachment) = &message.attachment {
+            let user_counts = counts.entry(message.owner.clone()).or_default();
+            *user_counts.entry(attachment.kind.to_string()).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Sums the size in bytes of the media files each user shared, looking the
+/// attachments up in the media bundled with a `.zip` export.
+/// Attachments without a matching bundled file are not counted.
+pub fn media_bytes_per_user(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+    media: &MediaIndex,
+) -> Result<HashMap<String, i64>, DatabaseError> {
+    let mut bytes: HashMap<String, i64> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        let file = message
+            .attachment
+            .as_ref()
+            .and_then(|attachment| attachment.file_name.as_deref())
+            .and_then(|name| media.get(name));
+        if let Some(file) = file {
+            *bytes.entry(message.owner.clone()).or_insert(0) += file.size as i64;
+        }
+    }
+    Ok(bytes)
+}
+
+/// How many of a user's messages were deleted and how many were edited.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EditCounts {
+    pub deleted: i64,
+    pub edited: i64,
+}
+
+/// Counts the deleted and edited messages of each user.
+/// Users who never deleted or edited a message are left out.
+pub fn edits_per_user(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, EditCounts>, DatabaseError> {
+    let mut counts: HashMap<String, EditCounts> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        if message.deleted || message.edited {
+            let user_counts = counts.entry(message.owner.clone()).or_default();
+            user_counts.deleted += message.deleted as i64;
+            user_counts.edited += message.edited as i64;
+        }
+    }
+    Ok(counts)
+}
+
+/// Counts who mentions whom.
+/// Returns a map of username to a map of mentioned participant to count.
+pub fn mentions_per_user(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, HashMap<String, i64>>, DatabaseError> {
+    let mut counts: HashMap<String, HashMap<String, i64>> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        for mentioned in &message.mentions {
+            let user_counts = counts.entry(message.owner.clone()).or_default();
+            *user_counts.entry(mentioned.clone()).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Counts the links shared to each domain, e.g. `youtube.com`.
+pub fn links_per_domain(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, i64>, DatabaseError> {
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        for domain in message.links.iter().filter_map(|link| link_domain(link)) {
+            *counts.entry(domain).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Counts the links each user shared, broken down by domain.
+/// Returns a map of username to a map of domain to count.
+pub fn domains_per_user(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, HashMap<String, i64>>, DatabaseError> {
+    let mut counts: HashMap<String, HashMap<String, i64>> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        for domain in message.links.iter().filter_map(|link| link_domain(link)) {
+            let user_counts = counts.entry(message.owner.clone()).or_default();
+            *user_counts.entry(domain).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Counts how many times each emoji was sent across all messages.
+pub fn emoji_count(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, i64>, DatabaseError> {
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        for emoji in extract_emoji(&message.text) {
+            *counts.entry(emoji).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Counts the emoji each user sent.
+/// Returns a map of username to a map of emoji to count.
+pub fn emoji_per_user(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, HashMap<String, i64>>, DatabaseError> {
+    let mut counts: HashMap<String, HashMap<String, i64>> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        for emoji in extract_emoji(&message.text) {
+            let user_counts = counts.entry(message.owner.clone()).or_default();
+            *user_counts.entry(emoji).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Computes the average number of emoji per message of each user.
+/// Users who never sent an emoji are listed with 0.0.
+pub fn emoji_per_message(
+    messages: impl IntoIterator<Item = impl Borrow<Message>>,
+) -> Result<HashMap<String, f64>, DatabaseError> {
+    let mut totals: HashMap<String, (usize, usize)> = HashMap::new();
+    for message in user_messages(messages) {
+        let message = message.borrow();
+        let (sent, emoji) = totals.entry(message.owner.clone()).or_default();
+        *sent += 1;
+        *emoji += extract_emoji(&message.text).len();
+    }
+    Ok(totals
+        .into_iter()
+        .map(|(user, (sent, emoji))| (user, emoji as f64 / sent as f64))
+        .collect())
+}
+
+/// Fewest times a user must write a word or phrase for it to count as
+/// characteristic of them.
+const MIN_DISTINCTIVE_USES: i64 = 2;
+
+/// The words and phrases a user writes noticeably more often than the rest
+/// of the group, most distinctive first.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DistinctiveTerms {
+    pub words: Vec<String>,
+    /// Two- and three-word phrases, such as catchphrases.
+    pub phrases: Vec<String>,
+}
+
+/// Scores the terms of each user against those of the whole group, in the
+/// spirit of TF-IDF: a term's share of the user's terms, weighted by the log
+/// of how much larger that share is than the term's share of the group's
+/// terms. Terms everybody writes as often score 0.
+/// Returns the terms of each user with a positive score, best first.
+fn distinctive_terms(
+    counts: &HashMap<String, HashMap<String, i64>>,
+) -> HashMap<String, Vec<String>> {
+    let mut group: HashMap<&str, i64> = HashMap::new();
+    for user_counts in counts.values() {
+        for (term, count) in user_counts {
+            *group.entry(term).or_insert(0) += count;
+        }
+    }
+    let group_total: i64 = group.values().sum();
+    let mut terms = HashMap::new();
+    for (user, user_counts) in counts {
+        let user_total: i64 = user_counts.values().sum();
+        let mut scored: Vec<(&String, f64)> = user_counts
+            .iter()
+            .filter(|(_, count)| **count >= MIN_DISTINCTIVE_USES)
+            .map(|(term, count)| {
+                let share = *count as f64 / user_total as f64;
+                let group_share = group[term.as_str()] as f64 / group_total as f64;
+                (term, share * (share / group_share).ln())
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        scored.sort_by(|(a_term, a), (b_term, b)| b.total_cmp(a).then_with(|| a_term.cmp(b_term)));
+        let user_terms = scored.into_iter().map(|(term, _)| term.clone()).collect();
+        terms.insert(user.clone(), user_terms);
+    }
+    terms
+}
+
+/// Finds the `top` most distinctive words and phrases of each user, scored
+/// against the rest of the group. A term has to be written at least twice to
+/// be distinctive, and in a chat with a single participant nothing is.
+/// Words and phrases are split and folded by the tokenizer, and its
+/// stopwords are left out. A phrase is skipped when it is part of, or
+/// contains, a phrase ranked above it.
+pub fn distinctive_terms_per_user(
+    messages: &[Message],
+    tokenizer: &Tokenizer,
+    top: usize,
+) -> Result<HashMap<String, DistinctiveTerms>, DatabaseError> {
+    let mut word_counts: HashMap<String, HashMap<String, i64>> = HashMap::new();
+    for message in user_messages(messages) {
+        let user_counts = word_counts.entry(message.owner.clone()).or_default();
+        for word in message_words(message, tokenizer) {
+            if !tokenizer.is_stopword(&word) {
+                *user_counts.entry(word).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut phrase_counts = ngrams_per_user(messages, 2, tokenizer)?;
+    for (user, trigrams) in ngrams_per_user(messages, 3, tokenizer)? {
+        phrase_counts.entry(user).or_default().extend(trigrams);
+    }
+
+    let mut words = distinctive_terms(&word_counts);
+    let mut phrases = distinctive_terms(&phrase_counts);
+    let mut terms = HashMap::new();
+    for user in word_counts.into_keys() {
+        let user_words = words.remove(&user).unwrap_or_default();
+        let mut user_phrases: Vec<String> = Vec::new();
+        for phrase in phrases.remove(&user).unwrap_or_default() {
+            let padded = format!(" {} ", phrase);
+            let overlaps = user_phrases.iter().any(|kept| {
+                let kept = format!(" {} ", kept);
+                kept.contains(&padded) || padded.contains(&kept)
+            });
+            if !overlaps && user_phrases.len() < top {
+                user_phrases.push(phrase);
+            }
+        }
+        let distinctive = DistinctiveTerms {
+            words: user_words.into_iter().take(top).collect(),
+            phrases: user_phrases,
+        };
+        terms.insert(user, distinctive);
+    }
+    Ok(terms)
+}
@@ -0,0 +1,117 @@
+use crate::message::Message;
+use crate::stats::user_messages;
+use crate::tokenizer::{Tokenizer, remove_accents};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Built-in stopword lists, by language code.
+const LANGUAGES: [(&str, &str); 8] = [
+    ("en", include_str!("en.txt")),
+    ("es", include_str!("es.txt")),
+    ("pt", include_str!("pt.txt")),
+    ("fr", include_str!("fr.txt")),
+    ("de", include_str!("de.txt")),
+    ("it", include_str!("it.txt")),
+    ("nl", include_str!("nl.txt")),
+    ("ru", include_str!("ru.txt")),
+];
+
+/// Smallest share of a chat's words a language's stopwords need to make up
+/// for the chat to be taken as written in that language.
+const MIN_DETECTED_SHARE: f64 = 0.1;
+
+/// Words too common to tell anything about a chat, such as "the" or "que",
+/// left out of word frequencies.
+#[derive(Debug, Clone, Default)]
+pub struct Stopwords {
+    words: HashSet<String>,
+}
+
+impl Stopwords {
+    /// Reads a list of stopwords written one per line. Blank lines and lines
+    /// starting with `#` are skipped.
+    pub fn parse(list: &str) -> Stopwords {
+        let mut stopwords = Stopwords::default();
+        for word in list
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            stopwords.insert(word);
+        }
+        stopwords
+    }
+
+    /// Reads a stopword file in the format of [`Stopwords::parse`].
+    pub fn from_file(path: &Path) -> io::Result<Stopwords> {
+        Ok(Stopwords::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Returns the built-in stopwords of a language, e.g. `en` or `es`.
+    pub fn language(code: &str) -> Option<Stopwords> {
+        LANGUAGES
+            .iter()
+            .find(|(language, _)| language.eq_ignore_ascii_case(code))
+            .map(|(_, list)| Stopwords::parse(list))
+    }
+
+    /// Codes of the languages with built-in stopwords.
+    pub fn languages() -> impl Iterator<Item = &'static str> {
+        LANGUAGES.iter().map(|(language, _)| *language)
+    }
+
+    /// Guesses the language of a chat from the built-in stopwords its
+    /// messages use the most.
+    ///
+    /// Returns `None` if no language's stopwords make up a tenth of the words.
+    pub fn detect_language(messages: &[Message]) -> Option<&'static str> {
+        let lists: Vec<(&str, Stopwords)> = LANGUAGES
+            .iter()
+            .map(|(language, list)| (*language, Stopwords::parse(list)))
+            .collect();
+        let tokenizer = Tokenizer::new();
+        let mut words = 0;
+        let mut hits: HashMap<&str, usize> = HashMap::new();
+        for message in user_messages(messages) {
+            for word in tokenizer.tokens(&message.text) {
+                words += 1;
+                for (language, stopwords) in &lists {
+                    if stopwords.contains(&word) {
+                        *hits.entry(language).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        hits.into_iter()
+            .filter(|(_, count)| *count as f64 >= words as f64 * MIN_DETECTED_SHARE)
+            .max_by_key(|(language, count)| (*count, std::cmp::Reverse(*language)))
+            .map(|(language, _)| language)
+    }
+
+    /// Adds the stopwords of another list to this one.
+    pub fn extend(&mut self, other: Stopwords) {
+        self.words.extend(other.words);
+    }
+
+    /// Checks whether a word is a stopword, ignoring case and accents.
+    pub fn contains(&self, word: &str) -> bool {
+        !self.words.is_empty() && self.words.contains(&fold(word))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    fn insert(&mut self, word: &str) {
+        self.words.insert(fold(word));
+    }
+}
+
+/// Lowercases a word and removes its accents, and writes typographic
+/// apostrophes as plain ones, so that stopwords match however the chat
+/// writes them.
+fn fold(word: &str) -> String {
+    remove_accents(word).replace('\u{2019}', "'").to_lowercase()
+}
@@ -0,0 +1,157 @@
+/// A group event WhatsApp writes into the export instead of a user message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SystemEvent {
+    /// "Messages and calls are end-to-end encrypted..."
+    EncryptionNotice,
+    /// "Alice created group "Trip""
+    GroupCreated { actor: String, subject: String },
+    /// "Alice added Bob and Carol"
+    MemberAdded { actor: String, members: Vec<String> },
+    /// "Alice removed Bob"
+    MemberRemoved { actor: String, member: String },
+    /// "Bob left"
+    MemberLeft { member: String },
+    /// "Bob joined using this group's invite link"
+    MemberJoined { member: String },
+    /// "Alice changed the subject from "Trip" to "Trip 2023""
+    SubjectChanged { actor: String, subject: String },
+    /// "Alice changed this group's icon"
+    IconChanged { actor: String },
+    /// "Alice changed the group description"
+    DescriptionChanged { actor: String },
+    /// "Your security code with Bob changed"
+    SecurityCodeChanged { member: String },
+    /// Any other notice that is not attributed to a sender.
+    Other(String),
+}
+
+impl SystemEvent {
+    /// Recognizes the text of a known system event.
+    ///
+    /// Returns `None` if the text doesn't match any of the known events.
+    pub fn parse(text: &str) -> Option<SystemEvent> {
+        let text = text.trim().trim_end_matches('.');
+        if text.starts_with("Messages") && text.contains("end-to-end encrypt") {
+            return Some(SystemEvent::EncryptionNotice);
+        }
+        if let Some(rest) = text.strip_prefix("Your security code with ") {
+            let member = rest.split(" changed").next()?;
+            return Some(SystemEvent::SecurityCodeChanged {
+                member: member.to_string(),
+            });
+        }
+        if let Some((member, _)) = text
+            .split_once("'s security code changed")
+            .or_else(|| text.split_once("’s security code changed"))
+        {
+            return Some(SystemEvent::SecurityCodeChanged {
+                member: member.to_string(),
+            });
+        }
+        if let Some(member) = text.strip_suffix(" left") {
+            return Some(SystemEvent::MemberLeft {
+                member: member.to_string(),
+            });
+        }
+        if let Some((member, _)) = text.split_once(" joined using this group") {
+            return Some(SystemEvent::MemberJoined {
+                member: member.to_string(),
+            });
+        }
+        if let Some((actor, subject)) = text.split_once(" created group ") {
+            return Some(SystemEvent::GroupCreated {
+                actor: actor.to_string(),
+                subject: unquote(subject),
+            });
+        }
+        for phrase in [" changed the subject ", " changed the group name "] {
+            if let Some((actor, rest)) = text.split_once(phrase) {
+                let subject = match rest.rsplit_once(" to ") {
+                    Some((_, subject)) => subject,
+                    None => rest.strip_prefix("to ")?,
+                };
+                return Some(SystemEvent::SubjectChanged {
+                    actor: actor.to_string(),
+                    subject: unquote(subject),
+                });
+            }
+        }
+        for phrase in [
+            " changed this group's icon",
+            " changed this group’s icon",
+            " changed the group icon",
+            " deleted this group's icon",
+            " deleted this group’s icon",
+        ] {
+            if let Some((actor, _)) = text.split_once(phrase) {
+                return Some(SystemEvent::IconChanged {
+                    actor: actor.to_string(),
+                });
+            }
+        }
+        for phrase in [
+            " changed the group description",
+            " deleted the group description",
+        ] {
+            if let Some((actor, _)) = text.split_once(phrase) {
+                return Some(SystemEvent::DescriptionChanged {
+                    actor: actor.to_string(),
+                });
+            }
+        }
+        if let Some((actor, members)) = text.split_once(" added ") {
+            return Some(SystemEvent::MemberAdded {
+                actor: actor.to_string(),
+                members: members
+                    .split(", ")
+                    .flat_map(|member| member.split(" and "))
+                    .map(str::to_string)
+                    .collect(),
+            });
+        }
+        if let Some((actor, member)) = text.split_once(" removed ") {
+            return Some(SystemEvent::MemberRemoved {
+                actor: actor.to_string(),
+                member: member.to_string(),
+            });
+        }
+        None
+    }
+
+    /// Returns the person who caused the event, if the event names one.
+    pub fn actor(&self) -> Option<&str> {
+        match self {
+            SystemEvent::GroupCreated { actor, .. }
+            | SystemEvent::MemberAdded { actor, .. }
+            | SystemEvent::MemberRemoved { actor, .. }
+            | SystemEvent::SubjectChanged { actor, .. }
+            | SystemEvent::IconChanged { actor }
+            | SystemEvent::DescriptionChanged { actor } => Some(actor),
+            SystemEvent::MemberLeft { member }
+            | SystemEvent::MemberJoined { member }
+            | SystemEvent::SecurityCodeChanged { member } => Some(member),
+            SystemEvent::EncryptionNotice | SystemEvent::Other(_) => None,
+        }
+    }
+
+    /// Returns every participant named by the event.
+    pub fn participants(&self) -> Vec<&str> {
+        let mut participants: Vec<&str> = self.actor().into_iter().collect();
+        match self {
+            SystemEvent::MemberAdded { members, .. } => {
+                participants.extend(members.iter().map(String::as_str))
+            }
+            SystemEvent::MemberRemoved { member, .. } => participants.push(member),
+            _ => {}
+        }
+        participants
+    }
+}
+
+/// Strips the straight or curly quotes WhatsApp puts around group subjects.
+fn unquote(subject: &str) -> String {
+    subject
+        .trim()
+        .trim_matches(|c| c == '"' || c == '“' || c == '”')
+        .to_string()
+}
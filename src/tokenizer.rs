@@ -0,0 +1,99 @@
+use crate::stopwords::Stopwords;
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Splits message texts into words, the same way for every word stat.
+///
+/// Words are found at Unicode word boundaries, so punctuation is left out
+/// and `hello,` and `hello!` are both `hello`, while `don't` stays one word.
+/// Words are lowercased unless [`Tokenizer::keep_case`] is set, and can have
+/// their accents removed with [`Tokenizer::fold_accents`]. Stopwords set
+/// with [`Tokenizer::with_stopwords`] are left out of word frequencies.
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    fold_case: bool,
+    fold_accents: bool,
+    stopwords: Stopwords,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Tokenizer {
+            fold_case: true,
+            fold_accents: false,
+            stopwords: Stopwords::default(),
+        }
+    }
+}
+
+impl Tokenizer {
+    pub fn new() -> Self {
+        Tokenizer::default()
+    }
+
+    /// Keeps words as written instead of lowercasing them.
+    pub fn keep_case(mut self) -> Self {
+        self.fold_case = false;
+        self
+    }
+
+    /// Removes accents from words, so that `café` and `cafe` are the same word.
+    pub fn fold_accents(mut self) -> Self {
+        self.fold_accents = true;
+        self
+    }
+
+    /// Sets the words to leave out of word frequencies.
+    pub fn with_stopwords(mut self, stopwords: Stopwords) -> Self {
+        self.stopwords = stopwords;
+        self
+    }
+
+    /// Checks whether a word returned by [`Tokenizer::tokens`] is a stopword.
+    pub fn is_stopword(&self, word: &str) -> bool {
+        self.stopwords.contains(word)
+    }
+
+    /// Returns the words of a text, in order.
+    pub fn tokens(&self, text: &str) -> Vec<String> {
+        text.unicode_words().map(|word| self.fold(word)).collect()
+    }
+
+    /// Returns the runs of words of a text that are only separated by
+    /// whitespace, so that a phrase can't run across punctuation such as
+    /// the end of a sentence.
+    pub fn phrases(&self, text: &str) -> Vec<Vec<String>> {
+        let mut phrases = vec![Vec::new()];
+        for segment in text.split_word_bounds() {
+            if segment.chars().any(char::is_alphanumeric) {
+                phrases.last_mut().unwrap().push(self.fold(segment));
+            } else if !segment.trim().is_empty() {
+                phrases.push(Vec::new());
+            }
+        }
+        phrases.retain(|phrase| !phrase.is_empty());
+        phrases
+    }
+
+    /// Applies the case and accent folding of the tokenizer to a single word.
+    fn fold(&self, word: &str) -> String {
+        let word = if self.fold_accents {
+            remove_accents(word)
+        } else {
+            word.to_string()
+        };
+        if self.fold_case {
+            word.to_lowercase()
+        } else {
+            word
+        }
+    }
+}
+
+/// Removes the accents of the letters of a word, e.g. `crème` to `creme`.
+pub(crate) fn remove_accents(word: &str) -> String {
+    word.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .nfc()
+        .collect()
+}
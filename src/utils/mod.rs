@@ -1 +1,2 @@
+pub mod text_utils;
 pub mod time_utils;
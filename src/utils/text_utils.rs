@@ -0,0 +1,51 @@
+use std::ops::Range;
+
+/// Checks for the invisible formatting characters found in exports: the byte
+/// order mark, zero-width space and the directional marks, embeddings and
+/// isolates WhatsApp puts around names and system texts.
+pub fn is_invisible_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{FEFF}'
+            | '\u{200B}'
+            | '\u{200E}'
+            | '\u{200F}'
+            | '\u{061C}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2066}'..='\u{2069}'
+    )
+}
+
+/// Replaces the no-break spaces exports use (U+00A0 and the narrow U+202F)
+/// with plain spaces.
+pub fn normalize_spaces(text: &str) -> String {
+    text.replace(['\u{00A0}', '\u{202F}'], " ")
+}
+
+/// Prepares a raw export line for header detection: drops the invisible
+/// marks in front of it and normalizes no-break spaces.
+pub fn normalize_line(line: &str) -> String {
+    normalize_spaces(line.trim_start_matches(is_invisible_mark))
+}
+
+/// Cleans up a sender name so the same person is always spelled the same:
+/// removes every invisible mark, normalizes no-break spaces and trims.
+pub fn normalize_name(name: &str) -> String {
+    let name: String = name.chars().filter(|c| !is_invisible_mark(*c)).collect();
+    normalize_spaces(&name).trim().to_string()
+}
+
+/// Returns the text with each of the given byte ranges replaced by a space,
+/// so that what they cover is left out without gluing its neighbours
+/// together. The ranges must be in order and not overlap.
+pub fn blank_spans(text: &str, spans: impl IntoIterator<Item = Range<usize>>) -> String {
+    let mut blanked = String::with_capacity(text.len());
+    let mut last = 0;
+    for span in spans {
+        blanked.push_str(&text[last..span.start]);
+        blanked.push(' ');
+        last = span.end;
+    }
+    blanked.push_str(&text[last..]);
+    blanked
+}
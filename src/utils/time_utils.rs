@@ -1,7 +1,213 @@
+use crate::errors::ParseError;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::str::FromStr;
+
+/// Localized markers WhatsApp writes after (or before) the time for the first
+/// half of the day on a 12-hour clock. Compared lowercase with spaces removed.
+const AM_MARKERS: &[&str] = &[
+    "am", "a.m.", "vorm.", "fm", "πμ", "π.μ.", "ص", "午前", "上午", "오전",
+];
+
+/// Localized markers for the second half of the day on a 12-hour clock.
+const PM_MARKERS: &[&str] = &[
+    "pm", "p.m.", "nachm.", "em", "μμ", "μ.μ.", "م", "午後", "下午", "오후",
+];
+
 pub fn get_hour(time: &str) -> String {
     if let Some(x) = time.split(":").next() {
-        return x.to_string();
+        x.to_string()
     } else {
         String::from("00")
     }
 }
+
+/// Converts a time from an export into 24-hour `HH:MM` (or `HH:MM:SS`) form.
+///
+/// Accepts 24-hour times as well as 12-hour times with an AM/PM marker in
+/// front of or behind the clock, including localized markers and the
+/// no-break spaces WhatsApp puts before them.
+///
+/// Returns `None` if the time can't be understood.
+pub fn normalize_time(raw: &str) -> Option<String> {
+    let raw = raw.replace(['\u{202F}', '\u{00A0}'], " ");
+    let start = raw.find(|c: char| c.is_ascii_digit())?;
+    let end = raw[start..]
+        .find(|c: char| !c.is_ascii_digit() && c != ':')
+        .map_or(raw.len(), |offset| start + offset);
+    let marker: String = raw[..start]
+        .chars()
+        .chain(raw[end..].chars())
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+
+    let mut parts = raw[start..end].split(':');
+    let mut hour: u32 = parts.next()?.parse().ok()?;
+    let minute: u32 = parts.next()?.parse().ok()?;
+    let second: Option<u32> = match parts.next() {
+        Some(second) => Some(second.parse().ok()?),
+        None => None,
+    };
+    if parts.next().is_some() || minute > 59 || second.is_some_and(|s| s > 59) {
+        return None;
+    }
+
+    if marker.is_empty() {
+        if hour > 23 {
+            return None;
+        }
+    } else {
+        let is_pm = if AM_MARKERS.contains(&marker.as_str()) {
+            false
+        } else if PM_MARKERS.contains(&marker.as_str()) {
+            true
+        } else {
+            return None;
+        };
+        if !(1..=12).contains(&hour) {
+            return None;
+        }
+        hour %= 12;
+        if is_pm {
+            hour += 12;
+        }
+    }
+
+    Some(match second {
+        Some(second) => format!("{:02}:{:02}:{:02}", hour, minute, second),
+        None => format!("{:02}:{:02}", hour, minute),
+    })
+}
+
+/// The order of the day, month and year fields in an export's dates, which
+/// depends on the locale of the phone the chat was exported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// `31/12/22`
+    DayMonthYear,
+    /// `12/31/22`
+    MonthDayYear,
+    /// `2022-12-31`
+    YearMonthDay,
+}
+
+impl FromStr for DateOrder {
+    type Err = ParseError;
+
+    /// Reads a date order written as `dmy`, `mdy` or `ymd`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dmy" => Ok(DateOrder::DayMonthYear),
+            "mdy" => Ok(DateOrder::MonthDayYear),
+            "ymd" => Ok(DateOrder::YearMonthDay),
+            _ => Err(ParseError::UnknownDateOrder(s.to_string())),
+        }
+    }
+}
+
+/// Works out the date order of an export by looking at all of its dates.
+///
+/// A date whose first field has four digits is year first, a first field
+/// above 12 can only be a day and a second field above 12 can only be a day.
+#[derive(Debug, Default)]
+pub struct DateOrderDetector {
+    day_first: bool,
+    month_first: bool,
+    year_first: bool,
+    short_dates: bool,
+}
+
+impl DateOrderDetector {
+    /// Records the evidence a single date gives about the order.
+    pub fn observe(&mut self, date: &str) {
+        let Some([first, second, _]) = date_fields(date) else {
+            return;
+        };
+        if first.len() == 4 {
+            self.year_first = true;
+            return;
+        }
+        self.short_dates = true;
+        let first: u32 = first.parse().unwrap_or(0);
+        let second: u32 = second.parse().unwrap_or(0);
+        if first > 12 {
+            self.day_first = true;
+        }
+        if second > 12 {
+            self.month_first = true;
+        }
+    }
+
+    /// Returns the order as soon as an observed date has told day first and
+    /// month first apart, without waiting for the rest of the dates.
+    ///
+    /// Returns `Ok(None)` while undecided and
+    /// `Err(ParseError::ConflictingDateOrder)` once the dates disagree.
+    pub fn settled(&self) -> Result<Option<DateOrder>, ParseError> {
+        match (self.day_first, self.month_first) {
+            (true, true) => Err(ParseError::ConflictingDateOrder),
+            (true, false) => Ok(Some(DateOrder::DayMonthYear)),
+            (false, true) => Ok(Some(DateOrder::MonthDayYear)),
+            (false, false) => Ok(None),
+        }
+    }
+
+    /// Returns the order the observed dates were written in.
+    ///
+    /// Returns `Err(ParseError::AmbiguousDateOrder)` if no date tells the
+    /// orders apart and `Err(ParseError::ConflictingDateOrder)` if some dates
+    /// can only be day first while others can only be month first.
+    pub fn finish(&self) -> Result<DateOrder, ParseError> {
+        match (self.day_first, self.month_first) {
+            (true, true) => Err(ParseError::ConflictingDateOrder),
+            (true, false) => Ok(DateOrder::DayMonthYear),
+            (false, true) => Ok(DateOrder::MonthDayYear),
+            (false, false) if self.year_first && !self.short_dates => Ok(DateOrder::YearMonthDay),
+            (false, false) => Err(ParseError::AmbiguousDateOrder),
+        }
+    }
+}
+
+/// Splits a date such as `31/12/22`, `31.12.2022.` or `2022-12-31` into its
+/// three fields.
+fn date_fields(raw: &str) -> Option<[&str; 3]> {
+    let fields: Vec<&str> = raw
+        .split(['/', '.', '-'])
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .collect();
+    fields.try_into().ok()
+}
+
+/// Parses an export date such as `31/12/22`, `31.12.2022` or `2022-12-31`
+/// written in the given `order`.
+///
+/// Dates starting with a four-digit year are always read year first. Two-digit
+/// years are placed in the 2000s.
+///
+/// Returns `None` if the date can't be understood.
+pub fn parse_date(raw: &str, order: DateOrder) -> Option<NaiveDate> {
+    let [first, second, third] = date_fields(raw)?;
+    let (year, month, day) = match order {
+        _ if first.len() == 4 => (first, second, third),
+        DateOrder::DayMonthYear => (third, second, first),
+        DateOrder::MonthDayYear => (third, first, second),
+        DateOrder::YearMonthDay => (first, second, third),
+    };
+    let mut year: i32 = year.parse().ok()?;
+    if year < 100 {
+        year += 2000;
+    }
+    NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
+
+/// Combines an export date and a normalized 24-hour time into a timestamp.
+///
+/// Returns `None` if either part can't be understood.
+pub fn parse_timestamp(date: &str, time: &str, order: DateOrder) -> Option<NaiveDateTime> {
+    let date = parse_date(date, order)?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .ok()?;
+    Some(date.and_time(time))
+}
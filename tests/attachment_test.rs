@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::attachment::{Attachment, AttachmentKind};
+
+    #[test]
+    fn test_parse_placeholders() {
+        let (media, caption) = Attachment::parse("<Media omitted>").unwrap();
+        assert_eq!(media.kind, AttachmentKind::Media);
+        assert_eq!(caption, "");
+
+        let (sticker, _) = Attachment::parse("sticker omitted").unwrap();
+        assert_eq!(sticker.kind, AttachmentKind::Sticker);
+
+        let (photo, caption) =
+            Attachment::parse("<attached: 00000012-PHOTO-2022-01-01-12-00-00.jpg> at the beach")
+                .unwrap();
+        assert_eq!(photo.kind, AttachmentKind::Image);
+        assert_eq!(
+            photo.file_name.as_deref(),
+            Some("00000012-PHOTO-2022-01-01-12-00-00.jpg")
+        );
+        assert_eq!(caption, "at the beach");
+
+        let (voice, _) = Attachment::parse("PTT-20220101-WA0003.opus (file attached)").unwrap();
+        assert_eq!(voice.kind, AttachmentKind::Audio);
+
+        let (document, _) = Attachment::parse("Report.pdf • 3 pages document omitted").unwrap();
+        assert_eq!(document.kind, AttachmentKind::Document);
+        assert_eq!(document.file_name.as_deref(), Some("Report.pdf"));
+
+        assert!(Attachment::parse("I omitted nothing").is_none());
+    }
+}
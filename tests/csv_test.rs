@@ -0,0 +1,85 @@
+use std::path::Path;
+use whatsapp_stats::importers::csv::{CsvColumns, CsvImporter};
+
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{
+        diagnostics::RejectReason, errors::ParseError, message::Message, source::ChatSource,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_import_csv_with_column_mapping() {
+        let columns: CsvColumns = "Date,Author,Content".parse().unwrap();
+        let importer =
+            CsvImporter::new(Path::new("./tests/mocks/discord_messages.csv")).with_columns(columns);
+
+        let messages: Vec<Message> = importer.parse().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].owner, "Alice");
+        assert_eq!(messages[0].text, "Hello, everyone");
+        assert_eq!(messages[0].hour, "20:37:05");
+        assert_eq!(messages[1].text, "Multi\nline");
+        assert_eq!(messages[2].date, "2022-05-14");
+    }
+
+    #[test]
+    fn test_import_csv_unix_times() {
+        let importer = CsvImporter::new(Path::new("./tests/mocks/signal_messages.csv"));
+
+        let messages: Vec<Message> = importer.parse().unwrap();
+        let timestamps: Vec<String> = messages
+            .iter()
+            .filter_map(|m| m.timestamp.map(|t| t.to_string()))
+            .collect();
+        assert_eq!(
+            timestamps,
+            vec!["2022-05-13 18:37:05", "2022-05-13 18:38:00"]
+        );
+    }
+
+    #[test]
+    fn test_import_csv_errors() {
+        let importer = CsvImporter::new(Path::new("./tests/mocks/discord_messages.csv"));
+        assert!(matches!(
+            importer.parse(),
+            Err(ParseError::MissingCsvColumn(column)) if column == "timestamp"
+        ));
+
+        let importer = CsvImporter::new(Path::new("./tests/mocks/discord_messages.csv"))
+            .with_columns("Date,Author,Content".parse().unwrap())
+            .with_timestamp_format("%d/%m/%Y %H:%M")
+            .strict();
+        assert!(matches!(
+            importer.parse(),
+            Err(ParseError::InvalidTimestamp { line_number: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_import_csv_skips_malformed_rows() {
+        let importer = CsvImporter::new(Path::new("./tests/mocks/malformed_rows.csv"));
+        let (messages, report) = importer.parse_with_report().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(report.lines_read, 4);
+        let rejects: Vec<(usize, RejectReason)> = report
+            .rejects
+            .iter()
+            .map(|reject| (reject.line_number, reject.reason))
+            .collect();
+        assert_eq!(
+            rejects,
+            vec![
+                (3, RejectReason::InvalidTimestamp),
+                (4, RejectReason::MissingFields)
+            ]
+        );
+
+        let importer = CsvImporter::new(Path::new("./tests/mocks/malformed_rows.csv")).strict();
+        assert!(matches!(
+            importer.parse(),
+            Err(ParseError::InvalidTimestamp { line_number: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::Path;
+use whatsapp_stats::parser::Parser;
+
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{
+        source::ChatSource, stats::distinctive_terms_per_user, stopwords::Stopwords,
+        tokenizer::Tokenizer,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_distinctive_terms_per_user() {
+        let parser = Parser::new(Path::new("./tests/mocks/catchphrases.txt"));
+        let messages = parser.parse().unwrap();
+        let tokenizer = Tokenizer::new().with_stopwords(Stopwords::language("en").unwrap());
+
+        let terms = distinctive_terms_per_user(&messages, &tokenizer, 5).unwrap();
+        assert_eq!(terms["Alice"].words, vec!["awesome", "sauce"]);
+        assert_eq!(terms["Alice"].phrases, vec!["awesome sauce"]);
+        assert_eq!(terms["Bob"].words, vec!["good", "mate", "cheers", "lunch"]);
+        assert_eq!(terms["Bob"].phrases, vec!["cheers mate"]);
+        assert!(!terms["Alice"].words.contains(&"lunch".to_string()));
+    }
+
+    #[test]
+    fn test_distinctive_terms_top() {
+        let parser = Parser::new(Path::new("./tests/mocks/catchphrases.txt"));
+        let messages = parser.parse().unwrap();
+
+        let terms = distinctive_terms_per_user(&messages, &Tokenizer::new(), 1).unwrap();
+        assert_eq!(terms["Alice"].words.len(), 1);
+        assert_eq!(terms["Bob"].phrases.len(), 1);
+
+        let alone: Vec<_> = messages
+            .into_iter()
+            .filter(|message| message.owner == "Alice")
+            .collect();
+        let terms = distinctive_terms_per_user(&alone, &Tokenizer::new(), 5).unwrap();
+        assert_eq!(terms["Alice"], Default::default());
+    }
+}
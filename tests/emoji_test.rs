@@ -0,0 +1,51 @@
+use std::path::Path;
+use whatsapp_stats::parser::Parser;
+
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{
+        emoji::{extract_emoji, strip_emoji},
+        source::ChatSource,
+        stats::{emoji_count, emoji_per_message, emoji_per_user, total_word_count},
+        tokenizer::Tokenizer,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_extract_emoji() {
+        assert_eq!(extract_emoji("👨‍👩‍👧 and 👍🏽, from 🇬🇧!"), vec!["👨‍👩‍👧", "👍🏽", "🇬🇧"]);
+        assert_eq!(extract_emoji("I ❤ it"), vec!["❤️"]);
+        assert_eq!(extract_emoji("1️⃣ then #"), vec!["1️⃣"]);
+        assert!(extract_emoji("© 2022 Acme™ 123").is_empty());
+    }
+
+    #[test]
+    fn test_strip_emoji() {
+        assert_eq!(strip_emoji("haha😂😂"), "haha  ");
+    }
+
+    #[test]
+    fn test_emoji_stats() {
+        let parser = Parser::new(Path::new("./tests/mocks/emoji.txt"));
+
+        let messages = parser.parse().unwrap();
+        let counts = emoji_count(&messages).unwrap();
+        assert_eq!(counts["😂"], 2);
+        assert_eq!(counts["🏳️‍🌈"], 1);
+        assert_eq!(counts["❤️"], 1);
+        assert_eq!(counts.len(), 6);
+
+        let per_user = emoji_per_user(&messages).unwrap();
+        assert_eq!(per_user["Alice"]["😂"], 2);
+        assert_eq!(per_user["Bob"]["🇬🇧"], 1);
+
+        let ratios = emoji_per_message(&messages).unwrap();
+        assert_eq!(ratios["Alice"], 2.0);
+        assert_eq!(ratios["Bob"], 1.5);
+
+        let words = total_word_count(&messages, &Tokenizer::new()).unwrap();
+        assert_eq!(words["haha"], 1);
+        assert!(words.keys().all(|word| extract_emoji(word).is_empty()));
+    }
+}
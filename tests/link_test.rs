@@ -0,0 +1,74 @@
+use std::path::Path;
+use whatsapp_stats::parser::Parser;
+
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{
+        link::{extract_links, link_domain, strip_links},
+        source::ChatSource,
+        stats::{domains_per_user, links_per_domain, total_word_count, words_sent},
+        tokenizer::Tokenizer,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_extract_links() {
+        assert_eq!(
+            extract_links("read https://example.com/a?b=1, then WWW.rust-lang.org."),
+            vec!["https://example.com/a?b=1", "WWW.rust-lang.org"]
+        );
+        assert_eq!(
+            extract_links("(https://en.wikipedia.org/wiki/Rust_(programming_language))"),
+            vec!["https://en.wikipedia.org/wiki/Rust_(programming_language)"]
+        );
+        assert!(extract_links("mail alice@example.com, no https:// or www. here").is_empty());
+        assert!(extract_links("xhttps://example.com").is_empty());
+    }
+
+    #[test]
+    fn test_strip_links() {
+        assert_eq!(
+            strip_links("look https://example.com now")
+                .split_whitespace()
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_link_domain() {
+        assert_eq!(
+            link_domain("https://www.YouTube.com/watch?v=1"),
+            Some("youtube.com".to_string())
+        );
+        assert_eq!(
+            link_domain("http://user@example.com:8080/path"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(link_domain("https://localhost/"), None);
+    }
+
+    #[test]
+    fn test_links_per_domain() {
+        let parser = Parser::new(Path::new("./tests/mocks/links.txt"));
+
+        let messages = parser.parse().unwrap();
+        assert_eq!(
+            messages[0].links,
+            vec!["https://www.YouTube.com/watch?v=dQw4w9WgXcQ"]
+        );
+        assert_eq!(messages[2].links, vec!["www.youtube.com/shorts/abc"]);
+        let domains = links_per_domain(&messages).unwrap();
+        assert_eq!(domains["youtube.com"], 2);
+        assert_eq!(domains["en.wikipedia.org"], 1);
+        assert_eq!(domains["youtu.be"], 1);
+        let per_user = domains_per_user(&messages).unwrap();
+        assert_eq!(per_user["Alice"]["youtube.com"], 2);
+        assert_eq!(per_user["Bob"]["github.com"], 1);
+
+        let words = total_word_count(&messages, &Tokenizer::new()).unwrap();
+        assert!(words.keys().all(|word| !word.contains("://")));
+        assert_eq!(words_sent(&messages).unwrap(), 10);
+    }
+}
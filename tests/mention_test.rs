@@ -0,0 +1,57 @@
+use std::path::Path;
+use whatsapp_stats::parser::Parser;
+
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{
+        mention::{extract_mentions, resolve_mention, strip_mentions},
+        source::ChatSource,
+        stats::{mentions_per_user, total_word_count},
+        tokenizer::Tokenizer,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_extract_mentions() {
+        let text = "Hey @Bob, @\u{2068}~ Carol\u{2069} and @+44 7700 900123! alice@example.com";
+        assert_eq!(
+            extract_mentions(text),
+            vec!["Bob", "~ Carol", "+44 7700 900123"]
+        );
+        assert_eq!(extract_mentions("see @Dave."), vec!["Dave"]);
+        assert!(extract_mentions("mail alice@example.com or @ me").is_empty());
+    }
+
+    #[test]
+    fn test_strip_mentions() {
+        assert_eq!(strip_mentions("thanks @Bob!").split_whitespace().count(), 2);
+    }
+
+    #[test]
+    fn test_resolve_mention() {
+        let participants = ["Alice Smith", "Bob", "+44 7700 900123", "Bob Jones"];
+        assert_eq!(resolve_mention("alice", &participants), "Alice Smith");
+        assert_eq!(resolve_mention("bob", &participants), "Bob");
+        assert_eq!(
+            resolve_mention("447700900123", &participants),
+            "+44 7700 900123"
+        );
+        assert_eq!(resolve_mention("Dave", &participants), "Dave");
+    }
+
+    #[test]
+    fn test_mentions_per_user() {
+        let parser = Parser::new(Path::new("./tests/mocks/mentions.txt"));
+
+        let messages = parser.parse().unwrap();
+        assert_eq!(messages[0].mentions, vec!["Bob", "~ Carol"]);
+        let mentions = mentions_per_user(&messages).unwrap();
+        assert_eq!(mentions["Bob"].get("Alice Smith"), Some(&1));
+        assert_eq!(mentions["~ Carol"].get("+44 7700 900123"), Some(&1));
+        assert_eq!(mentions["+44 7700 900123"].get("~ Carol"), Some(&1));
+
+        let words = total_word_count(&messages, &Tokenizer::new()).unwrap();
+        assert!(words.keys().all(|word| !word.starts_with('@')));
+    }
+}
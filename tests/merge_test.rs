@@ -0,0 +1,42 @@
+use std::path::Path;
+use whatsapp_stats::parser::Parser;
+
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{merge::merge_exports, message::Message, source::ChatSource};
+
+    use super::*;
+
+    #[test]
+    fn test_merge_overlapping_exports() {
+        let android = Parser::new(Path::new("./tests/mocks/overlap_android.txt"))
+            .parse()
+            .unwrap();
+        let ios = Parser::new(Path::new("./tests/mocks/overlap_ios.txt"))
+            .parse()
+            .unwrap();
+
+        let (merged, report) = merge_exports(vec![android, ios]);
+        let texts: Vec<&str> = merged.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["Hello", "ok", "ok", "See you", "Morning"]);
+        assert_eq!(report.messages, 5);
+        assert_eq!(report.duplicates, 3);
+    }
+
+    #[test]
+    fn test_merge_keeps_distinct_messages() {
+        let message = |owner: &str, text: &str| Message {
+            owner: owner.to_string(),
+            date: "13/5/22".to_string(),
+            hour: "20:37".to_string(),
+            text: text.to_string(),
+            ..Default::default()
+        };
+        let (merged, report) = merge_exports(vec![
+            vec![message("Alice", "Hi")],
+            vec![message("Bob", "Hi"), message("Alice", "Hi")],
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(report.duplicates, 1);
+    }
+}
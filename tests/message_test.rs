@@ -1,6 +1,10 @@
 #[cfg(test)]
 mod tests {
-    use whatsapp_stats::message::Message;
+    use whatsapp_stats::{
+        attachment::AttachmentKind,
+        message::{ExportFormat, Message, MessageKind},
+        system::SystemEvent,
+    };
 
     #[test]
     fn test_parse_one_message() {
@@ -10,8 +14,83 @@ mod tests {
             date: "3/5/22".to_string(),
             hour: "20:37".to_string(),
             text: "Hello".to_string(),
+            ..Default::default()
         };
         let parsed = Message::new(input).unwrap();
         assert_eq!(expected, parsed);
     }
+
+    #[test]
+    fn test_parse_android_message() {
+        let input = "31/12/22, 21:41 - test_user: Hello: world";
+        let expected = Message {
+            owner: "test_user".to_string(),
+            date: "31/12/22".to_string(),
+            hour: "21:41".to_string(),
+            text: "Hello: world".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(ExportFormat::detect(input), Some(ExportFormat::Android));
+        let parsed = Message::new(input).unwrap();
+        assert_eq!(expected, parsed);
+    }
+
+    #[test]
+    fn test_parse_12_hour_message() {
+        let input = "[12/31/22, 9:41:05\u{202F}PM] test_user: Hello";
+        let parsed = Message::new(input).unwrap();
+        assert_eq!(parsed.hour, "21:41:05");
+        assert_eq!(parsed.owner, "test_user");
+    }
+
+    #[test]
+    fn test_parse_system_messages() {
+        let android = Message::new("31/12/22, 21:41 - Alice added Bob and Carol").unwrap();
+        assert_eq!(
+            android.kind,
+            MessageKind::System(SystemEvent::MemberAdded {
+                actor: "Alice".to_string(),
+                members: vec!["Bob".to_string(), "Carol".to_string()],
+            })
+        );
+        assert_eq!(android.owner, "Alice");
+
+        let ios = Message::new("[31/12/22, 21:41:05] Trip: \u{200E}Bob left").unwrap();
+        assert_eq!(
+            ios.kind,
+            MessageKind::System(SystemEvent::MemberLeft {
+                member: "Bob".to_string()
+            })
+        );
+
+        let user = Message::new("31/12/22, 21:42 - Bob: Alice left").unwrap();
+        assert!(user.is_user());
+        assert_eq!(user.text, "Alice left");
+    }
+
+    #[test]
+    fn test_parse_media_message() {
+        let parsed = Message::new("[31/12/22, 21:41:05] Bob: \u{200E}image omitted").unwrap();
+        assert!(parsed.is_user());
+        assert_eq!(parsed.text, "");
+        assert_eq!(
+            parsed.attachment.map(|attachment| attachment.kind),
+            Some(AttachmentKind::Image)
+        );
+    }
+
+    #[test]
+    fn test_parse_deleted_and_edited_messages() {
+        let deleted = Message::new("31/12/22, 21:41 - Bob: This message was deleted").unwrap();
+        assert!(deleted.deleted);
+        assert_eq!(deleted.text, "");
+
+        let edited = Message::new(
+            "[31/12/22, 21:41:05] Bob: See you at 9 \u{200E}<This message was edited>",
+        )
+        .unwrap();
+        assert!(edited.edited);
+        assert!(!edited.deleted);
+        assert_eq!(edited.text, "See you at 9");
+    }
 }
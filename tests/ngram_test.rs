@@ -0,0 +1,54 @@
+use std::path::Path;
+use whatsapp_stats::parser::Parser;
+
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{
+        source::ChatSource,
+        stats::{ngram_count, ngrams_per_user},
+        stopwords::Stopwords,
+        tokenizer::Tokenizer,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_ngram_count() {
+        let parser = Parser::new(Path::new("./tests/mocks/phrases.txt"));
+        let messages = parser.parse().unwrap();
+
+        let bigrams = ngram_count(&messages, 2, &Tokenizer::new()).unwrap();
+        assert_eq!(bigrams.get("good morning"), Some(&3));
+        assert_eq!(bigrams.get("see you"), Some(&3));
+        assert_eq!(bigrams.get("soon good"), None);
+        assert_eq!(bigrams.get("cake good"), None);
+
+        let trigrams = ngram_count(&messages, 3, &Tokenizer::new()).unwrap();
+        assert_eq!(trigrams.get("piece of cake"), Some(&2));
+        assert_eq!(trigrams.get("see you soon"), Some(&2));
+
+        assert!(
+            ngram_count(&messages, 0, &Tokenizer::new())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_ngrams_skip_stopword_edges() {
+        let parser = Parser::new(Path::new("./tests/mocks/phrases.txt"));
+        let messages = parser.parse().unwrap();
+
+        let tokenizer = Tokenizer::new().with_stopwords(Stopwords::language("en").unwrap());
+        let trigrams = ngram_count(&messages, 3, &tokenizer).unwrap();
+        assert_eq!(trigrams.get("piece of cake"), Some(&2));
+        assert_eq!(trigrams.get("see you soon"), Some(&2));
+        assert_eq!(trigrams.get("in the park"), None);
+        assert_eq!(trigrams.get("you in the"), None);
+
+        let per_user = ngrams_per_user(&messages, 2, &tokenizer).unwrap();
+        assert_eq!(per_user["Alice"]["good morning"], 2);
+        assert_eq!(per_user["Bob"]["good morning"], 1);
+        assert_eq!(per_user["Bob"].get("see you"), None);
+    }
+}
@@ -1,9 +1,20 @@
+use std::io::Cursor;
 use std::path::Path;
-use whatsapp_stats::parser::Parser;
+use whatsapp_stats::{
+    parser::{MAX_HELD_BACK, MessageStream, Parser},
+    stats::messages_per_user,
+};
 
 #[cfg(test)]
 mod tests {
-    use whatsapp_stats::message::Message;
+    use whatsapp_stats::{
+        attachment::AttachmentKind,
+        diagnostics::RejectReason,
+        errors::ParseError,
+        message::{Message, MessageKind},
+        source::ChatSource,
+        system::SystemEvent,
+    };
 
     use super::*;
 
@@ -14,7 +25,7 @@ mod tests {
         );
 
         let parser = Parser::new(Path::new("./tests/mocks/test.txt"));
-        let content = parser.read_file();
+        let content = parser.read_file().unwrap();
         assert_eq!(expected_test, content)
     }
 
@@ -25,12 +36,14 @@ mod tests {
             date: "3/5/22".to_string(),
             hour: "20:37".to_string(),
             text: "Hello".to_string(),
+            ..Default::default()
         };
         let test_message_2 = Message {
             owner: "test_user".to_string(),
             date: "3/5/22".to_string(),
             hour: "20:37".to_string(),
             text: "GoodBye".to_string(),
+            ..Default::default()
         };
 
         let expected_test: Vec<Message> = vec![test_message_1, test_message_2];
@@ -39,4 +52,263 @@ mod tests {
         let parsed_array: Vec<Message> = parser.parse().unwrap();
         assert_eq!(expected_test, parsed_array)
     }
+
+    #[test]
+    fn test_parse_multiline() {
+        let parser = Parser::new(Path::new("./tests/mocks/multiline_messages.txt"));
+
+        let parsed_array: Vec<Message> = parser.parse().unwrap();
+        assert_eq!(parsed_array.len(), 2);
+        assert_eq!(
+            parsed_array[0].text,
+            "Hello\nthis is a second line\n\nand a third"
+        );
+        assert_eq!(parsed_array[1].text, "Bye");
+    }
+
+    #[test]
+    fn test_parse_android() {
+        let parser = Parser::new(Path::new("./tests/mocks/android_messages.txt"));
+
+        let parsed_array: Vec<Message> = parser.parse().unwrap();
+        assert_eq!(parsed_array.len(), 3);
+        assert_eq!(
+            parsed_array[0].kind,
+            MessageKind::System(SystemEvent::EncryptionNotice)
+        );
+        assert_eq!(parsed_array[1].owner, "Alice");
+        assert_eq!(parsed_array[1].text, "Happy new year\nsee you tomorrow");
+        assert_eq!(parsed_array[2].owner, "Bob");
+        assert_eq!(
+            parsed_array[2].timestamp.map(|t| t.to_string()),
+            Some("2022-12-31 21:45:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_ambiguous_dates() {
+        let parser = Parser::new(Path::new("./tests/mocks/mock_messages.txt"));
+        let (messages, report) = parser.parse_with_report().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|message| message.timestamp.is_none()));
+        assert_eq!(report.undated, 2);
+    }
+
+    #[test]
+    fn test_parse_edited_multiline() {
+        let parser = Parser::new(Path::new("./tests/mocks/edited_messages.txt"));
+
+        let parsed_array: Vec<Message> = parser.parse().unwrap();
+        assert_eq!(parsed_array[0].text, "Meet at 8\nnear the station");
+        assert!(parsed_array[0].edited);
+        assert!(parsed_array[1].deleted);
+    }
+
+    #[test]
+    fn test_parse_zip_export() {
+        let parser = Parser::new(Path::new("./tests/mocks/chat_export.zip"));
+
+        let parsed_array: Vec<Message> = parser.parse().unwrap();
+        assert_eq!(parsed_array.len(), 2);
+        assert_eq!(parsed_array[1].text, "Nice picture");
+
+        let media = parser.media_index().unwrap();
+        assert_eq!(media.len(), 1);
+        let photo = media.get("00000012-PHOTO-2022-05-13-20-37-00.jpg").unwrap();
+        assert_eq!(photo.size, 1024);
+        assert_eq!(photo.kind, AttachmentKind::Image);
+    }
+
+    #[test]
+    fn test_parse_strips_invisible_marks() {
+        let parser = Parser::new(Path::new("./tests/mocks/invisible_marks.txt"));
+
+        let parsed_array: Vec<Message> = parser.parse().unwrap();
+        let owners: Vec<&str> = parsed_array.iter().map(|m| m.owner.as_str()).collect();
+        assert_eq!(owners, vec!["Alice", "Alice", "~ Bob"]);
+        assert_eq!(parsed_array[0].text, "Hello");
+        assert!(parsed_array[1].attachment.is_some());
+    }
+
+    #[test]
+    fn test_parse_ios_notices() {
+        let parser = Parser::new(Path::new("./tests/mocks/ios_notices.txt"));
+
+        let parsed_array: Vec<Message> = parser.parse().unwrap();
+        let users: Vec<&str> = parsed_array
+            .iter()
+            .filter(|m| m.is_user())
+            .map(|m| m.owner.as_str())
+            .collect();
+        assert_eq!(users, vec!["Alice", "Bob", "Bob"]);
+        assert!(!parsed_array.iter().any(|m| m.owner == "Trip"));
+        assert_eq!(
+            parsed_array[2].kind,
+            MessageKind::System(SystemEvent::Other("You're now an admin".to_string()))
+        );
+        assert!(parsed_array[5].attachment.is_some());
+        assert!(parsed_array[6].deleted);
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let parser = Parser::new(Path::new("./tests/mocks/rejected_lines.txt"));
+
+        let (parsed_array, report) = parser.parse_with_report().unwrap();
+        assert_eq!(parsed_array.len(), 2);
+        assert_eq!(parsed_array[0].text, "Hi\nmore text");
+        assert_eq!(report.lines_read, 6);
+        assert_eq!(report.messages, 1);
+        assert_eq!(report.system_events, 1);
+        assert_eq!(report.continuations, 1);
+        let rejects: Vec<(usize, RejectReason)> = report
+            .rejects
+            .iter()
+            .map(|reject| (reject.line_number, reject.reason))
+            .collect();
+        assert_eq!(
+            rejects,
+            vec![
+                (1, RejectReason::BeforeFirstMessage),
+                (5, RejectReason::MalformedHeader)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let parser = Parser::new(Path::new("./tests/mocks/rejected_lines.txt")).strict();
+        assert!(matches!(
+            parser.parse(),
+            Err(ParseError::UnparseableLine {
+                line_number: 1,
+                reason: RejectReason::BeforeFirstMessage
+            })
+        ));
+
+        let parser = Parser::new(Path::new("./tests/mocks/android_messages.txt")).strict();
+        assert_eq!(parser.parse().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_message_stream() {
+        let chat = "3/5/22, 20:37 - Alice: Hello\nsecond line\n13/5/22, 09:00 - Bob: Bye\n";
+        let mut stream = MessageStream::new(Cursor::new(chat));
+
+        let first = stream.next().unwrap().unwrap();
+        assert_eq!(first.text, "Hello\nsecond line");
+        assert_eq!(
+            first.timestamp.map(|t| t.to_string()),
+            Some("2022-05-03 20:37:00".to_string())
+        );
+        assert_eq!(stream.next().unwrap().unwrap().owner, "Bob");
+        assert!(stream.next().is_none());
+        assert_eq!(stream.report().messages, 2);
+    }
+
+    #[test]
+    fn test_message_stream_ambiguous_dates() {
+        let chat = "3/5/22, 20:37 - Alice: Hello\n";
+        let mut stream = MessageStream::new(Cursor::new(chat));
+        let message = stream.next().unwrap().unwrap();
+        assert_eq!(message.text, "Hello");
+        assert_eq!(message.timestamp, None);
+        assert!(stream.next().is_none());
+        assert_eq!(stream.report().undated, 1);
+    }
+
+    #[test]
+    fn test_message_stream_holds_back_a_bounded_number_of_messages() {
+        let mut chat = "3/5/22, 20:37 - Alice: Hello\n".repeat(MAX_HELD_BACK + 1);
+        chat.push_str("13/5/22, 09:00 - Bob: Bye\n");
+
+        let mut stream = MessageStream::new(Cursor::new(chat.clone()));
+        assert_eq!(stream.next().unwrap().unwrap().timestamp, None);
+        assert_eq!(stream.by_ref().count(), MAX_HELD_BACK + 1);
+        assert_eq!(stream.report().undated, MAX_HELD_BACK + 1);
+
+        let mut stream = MessageStream::new(Cursor::new(chat.clone())).hold_back_all();
+        let first = stream.next().unwrap().unwrap();
+        assert_eq!(
+            first.timestamp.map(|t| t.to_string()),
+            Some("2022-05-03 20:37:00".to_string())
+        );
+        assert_eq!(stream.report().undated, 0);
+
+        let path = std::env::temp_dir().join("whatsapp_stats_held_back.txt");
+        std::fs::write(&path, chat).unwrap();
+        let parser = Parser::new(&path);
+        let mut stream = parser.messages().unwrap();
+        let first = stream.next().unwrap().unwrap();
+        assert_eq!(
+            first.timestamp.map(|t| t.to_string()),
+            Some("2022-05-03 20:37:00".to_string())
+        );
+        assert_eq!(stream.by_ref().count(), MAX_HELD_BACK + 1);
+        assert_eq!(stream.report().undated, 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_stats_from_message_stream() {
+        let parser = Parser::new(Path::new("./tests/mocks/android_messages.txt"));
+        let messages = parser.messages().unwrap().map(Result::unwrap);
+        let counts = messages_per_user(messages).unwrap();
+        assert_eq!(counts.get("Alice"), Some(&1));
+        assert_eq!(counts.get("Bob"), Some(&1));
+        assert_eq!(counts.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_compressed() {
+        for file in [
+            "./tests/mocks/android_messages.txt.gz",
+            "./tests/mocks/android_messages.txt.zst",
+        ] {
+            let parser = Parser::new(Path::new(file));
+            let parsed_array: Vec<Message> = parser.parse().unwrap();
+            assert_eq!(parsed_array.len(), 3);
+            assert_eq!(parsed_array[1].text, "Happy new year\nsee you tomorrow");
+        }
+
+        let parser = Parser::new(Path::new("./tests/mocks/chat_export.zip.gz"));
+        assert_eq!(parser.parse().unwrap().len(), 2);
+        assert_eq!(parser.media_index().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_utf16() {
+        for file in ["./tests/mocks/utf16le_bom.txt", "./tests/mocks/utf16be.txt"] {
+            let parser = Parser::new(Path::new(file));
+            let parsed_array: Vec<Message> = parser.parse().unwrap();
+            assert_eq!(parsed_array.len(), 3);
+            assert_eq!(parsed_array[1].owner, "Alice");
+            assert_eq!(parsed_array[1].text, "Happy new year\nsee you tomorrow");
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_encoding() {
+        let parser = Parser::new(Path::new("./tests/mocks/invalid_utf8.txt"));
+        let (parsed_array, report) = parser.parse_with_report().unwrap();
+        assert_eq!(parsed_array.len(), 1);
+        assert_eq!(parsed_array[0].owner, "Alice");
+        assert_eq!(report.rejects.len(), 1);
+        assert_eq!(report.rejects[0].line_number, 2);
+        assert_eq!(report.rejects[0].reason, RejectReason::InvalidEncoding);
+        assert_eq!(
+            report.rejects[0].content,
+            "31/12/22, 21:42 - Bob: caf\u{FFFD}"
+        );
+
+        let parser = Parser::new(Path::new("./tests/mocks/invalid_utf8.txt")).strict();
+        assert!(matches!(
+            parser.parse(),
+            Err(ParseError::InvalidEncoding(2))
+        ));
+        assert!(matches!(
+            parser.read_file(),
+            Err(ParseError::InvalidEncoding(2))
+        ));
+    }
 }
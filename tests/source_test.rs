@@ -0,0 +1,63 @@
+use whatsapp_stats::{
+    diagnostics::ParseReport, errors::ParseError, message::Message, source::ChatSource,
+};
+
+/// A chat source defined outside the library, yielding messages it holds.
+struct InMemorySource {
+    lines: Vec<(&'static str, &'static str)>,
+}
+
+impl ChatSource for InMemorySource {
+    fn parse_with_report(&self) -> Result<(Vec<Message>, ParseReport), ParseError> {
+        let messages: Vec<Message> = self
+            .lines
+            .iter()
+            .map(|(owner, text)| Message {
+                owner: owner.to_string(),
+                text: text.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let report = ParseReport::of_entries(&messages);
+        Ok((messages, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use whatsapp_stats::{
+        importers::telegram::TelegramImporter, parser::Parser, stats::messages_per_user,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_custom_source() {
+        let source = InMemorySource {
+            lines: vec![("Alice", "Hi"), ("Bob", "Hello"), ("Alice", "Bye")],
+        };
+
+        let messages = source.parse().unwrap();
+        let counts = messages_per_user(&messages).unwrap();
+        assert_eq!(counts.get("Alice"), Some(&2));
+        assert_eq!(source.check().unwrap().messages, 3);
+        assert!(source.media_index().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sources_behind_trait_objects() {
+        let sources: Vec<Box<dyn ChatSource>> = vec![
+            Box::new(Parser::new(Path::new("./tests/mocks/android_messages.txt"))),
+            Box::new(TelegramImporter::new(Path::new(
+                "./tests/mocks/telegram_result.json",
+            ))),
+        ];
+        let reports: Vec<(usize, usize)> = sources
+            .iter()
+            .map(|source| source.check().unwrap())
+            .map(|report| (report.messages, report.system_events))
+            .collect();
+        assert_eq!(reports, vec![(2, 1), (4, 2)]);
+    }
+}
@@ -1,14 +1,20 @@
+#![allow(unused_imports, clippy::useless_vec, clippy::unnecessary_cast)]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::NaiveDate;
     use std::collections::HashMap;
     use whatsapp_stats::{
-        message::Message,
+        attachment::{Attachment, AttachmentKind},
+        message::{Message, MessageKind},
         stats::{
             average_messages_per_user, average_words_per_message, count_phrase_per_user,
-            count_word_per_user, extract_word_count, longest_message_length, most_active_hour,
-            top_speaker_per_hour, total_word_count, words_sent,
+            count_word_per_user, edits_per_user, extract_word_count, longest_message_length,
+            media_per_user, messages_per_month, messages_per_user, messages_per_weekday,
+            most_active_hour, top_speaker_per_hour, total_word_count, words_sent,
         },
+        system::SystemEvent,
+        tokenizer::Tokenizer,
     };
 
     #[test]
@@ -18,16 +24,18 @@ mod tests {
             date: "3/5/22".to_string(),
             hour: "20:37".to_string(),
             text: "World Hello".to_string(),
+            ..Default::default()
         };
         let message_2 = Message {
             owner: "test_user".to_string(),
             date: "3/5/22".to_string(),
             hour: "20:37".to_string(),
             text: "Hello sir".to_string(),
+            ..Default::default()
         };
         let messages_array = vec![message_1, message_2];
         let count: HashMap<String, i64> =
-            count_word_per_user(&messages_array[..], "Hello").unwrap();
+            count_word_per_user(&messages_array[..], "Hello", &Tokenizer::new()).unwrap();
         assert_eq!(count.get("test_user"), Some(&(2 as i64)));
     }
 
@@ -38,19 +46,51 @@ mod tests {
             date: "3/5/22".to_string(),
             hour: "10:00".to_string(),
             text: "Rust is awesome".to_string(),
+            ..Default::default()
         };
         let message_2 = Message {
             owner: "Bob".to_string(),
             date: "3/5/22".to_string(),
             hour: "10:05".to_string(),
             text: "I think Rust is awesome too".to_string(),
+            ..Default::default()
         };
         let messages_array = vec![message_1, message_2];
-        let counts = count_phrase_per_user(&messages_array[..], "Rust is awesome").unwrap();
+        let counts =
+            count_phrase_per_user(&messages_array[..], "Rust is awesome", &Tokenizer::new())
+                .unwrap();
         assert_eq!(counts.get("Alice"), Some(&(1 as i64)));
         assert_eq!(counts.get("Bob"), Some(&(1 as i64)));
     }
 
+    #[test]
+    fn test_count_phrase_per_user_matches_whole_words() {
+        let messages = [
+            Message {
+                owner: "Alice".to_string(),
+                text: "Concatenate the strings".to_string(),
+                ..Default::default()
+            },
+            Message {
+                owner: "Bob".to_string(),
+                text: "My CAT, sat there".to_string(),
+                ..Default::default()
+            },
+            Message {
+                owner: "Carol".to_string(),
+                text: "The cat sat down".to_string(),
+                ..Default::default()
+            },
+        ];
+        let tokenizer = Tokenizer::new();
+        let counts = count_phrase_per_user(&messages, "cat", &tokenizer).unwrap();
+        assert_eq!(counts.get("Alice"), None);
+        assert_eq!(counts.get("Bob"), Some(&1));
+        let counts = count_phrase_per_user(&messages, "cat sat", &tokenizer).unwrap();
+        assert_eq!(counts.get("Bob"), None);
+        assert_eq!(counts.get("Carol"), Some(&1));
+    }
+
     #[test]
     fn test_total_word_count() {
         let message_1 = Message {
@@ -58,15 +98,17 @@ mod tests {
             date: "3/5/22".to_string(),
             hour: "20:37".to_string(),
             text: "Hello world".to_string(),
+            ..Default::default()
         };
         let message_2 = Message {
             owner: "Bob".to_string(),
             date: "3/5/22".to_string(),
             hour: "20:38".to_string(),
             text: "hello Rust".to_string(),
+            ..Default::default()
         };
         let messages_array = vec![message_1, message_2];
-        let counts = total_word_count(&messages_array[..]).unwrap();
+        let counts = total_word_count(&messages_array[..], &Tokenizer::new()).unwrap();
         assert_eq!(counts.get("hello"), Some(&(2 as i64))); // "Hello" + "hello"
         assert_eq!(counts.get("world"), Some(&(1 as i64)));
         assert_eq!(counts.get("rust"), Some(&(1 as i64)));
@@ -79,15 +121,17 @@ mod tests {
             date: "3/5/22".to_string(),
             hour: "20:37".to_string(),
             text: "Hello world hello".to_string(),
+            ..Default::default()
         };
         let message_2 = Message {
             owner: "Bob".to_string(),
             date: "3/5/22".to_string(),
             hour: "20:38".to_string(),
             text: "hello Rust".to_string(),
+            ..Default::default()
         };
         let messages_array = vec![message_1, message_2];
-        let count = extract_word_count(&messages_array[..], "hello").unwrap();
+        let count = extract_word_count(&messages_array[..], "hello", &Tokenizer::new()).unwrap();
         assert_eq!(count, 3);
     }
 
@@ -98,24 +142,28 @@ mod tests {
             date: "3/5/22".to_string(),
             hour: "09:00".to_string(),
             text: "Hi there".to_string(),
+            ..Default::default()
         };
         let message_2 = Message {
             owner: "Bob".to_string(),
             date: "3/5/22".to_string(),
             hour: "09:15".to_string(),
             text: "Hello Alice".to_string(),
+            ..Default::default()
         };
         let message_3 = Message {
             owner: "Alice".to_string(),
             date: "3/5/22".to_string(),
             hour: "09:30".to_string(),
             text: "How are you?".to_string(),
+            ..Default::default()
         };
         let message_4 = Message {
             owner: "Charlie".to_string(),
             date: "3/5/22".to_string(),
             hour: "10:00".to_string(),
             text: "Good morning".to_string(),
+            ..Default::default()
         };
         let messages_array = vec![message_1, message_2, message_3, message_4];
         let top_speakers = top_speaker_per_hour(&messages_array[..]).unwrap();
@@ -131,12 +179,14 @@ mod tests {
                 date: "1/1/22".to_string(),
                 hour: "12:00".to_string(),
                 text: "Hello world".to_string(),
+                ..Default::default()
             },
             Message {
                 owner: "Bob".to_string(),
                 date: "1/1/22".to_string(),
                 hour: "12:10".to_string(),
                 text: "Hi Alice, how are you?".to_string(),
+                ..Default::default()
             },
         ];
         let total = words_sent(&messages[..]).unwrap();
@@ -151,18 +201,21 @@ mod tests {
                 date: "1/1/22".to_string(),
                 hour: "09:05".to_string(),
                 text: "Morning".to_string(),
+                ..Default::default()
             },
             Message {
                 owner: "Bob".to_string(),
                 date: "1/1/22".to_string(),
                 hour: "09:15".to_string(),
                 text: "Hello".to_string(),
+                ..Default::default()
             },
             Message {
                 owner: "Alice".to_string(),
                 date: "1/1/22".to_string(),
                 hour: "10:00".to_string(),
                 text: "Later".to_string(),
+                ..Default::default()
             },
         ];
         let peak = most_active_hour(&messages[..]).unwrap();
@@ -177,12 +230,14 @@ mod tests {
                 date: "1/1/22".to_string(),
                 hour: "11:00".to_string(),
                 text: "Short msg".to_string(),
+                ..Default::default()
             },
             Message {
                 owner: "Bob".to_string(),
                 date: "1/1/22".to_string(),
                 hour: "11:10".to_string(),
                 text: "This is a much longer message than the first one".to_string(),
+                ..Default::default()
             },
         ];
         let longest = longest_message_length(&messages[..]).unwrap();
@@ -197,12 +252,14 @@ mod tests {
                 date: "1/1/22".to_string(),
                 hour: "12:00".to_string(),
                 text: "Hi".to_string(),
+                ..Default::default()
             },
             Message {
                 owner: "Bob".to_string(),
                 date: "1/1/22".to_string(),
                 hour: "12:10".to_string(),
                 text: "Hello world!".to_string(),
+                ..Default::default()
             },
         ];
         let avg = average_words_per_message(&messages[..]).unwrap();
@@ -217,22 +274,136 @@ mod tests {
                 date: "1/1/22".to_string(),
                 hour: "13:00".to_string(),
                 text: "Hi".to_string(),
+                ..Default::default()
             },
             Message {
                 owner: "Bob".to_string(),
                 date: "1/1/22".to_string(),
                 hour: "13:10".to_string(),
                 text: "Hey".to_string(),
+                ..Default::default()
             },
             Message {
                 owner: "Alice".to_string(),
                 date: "1/1/22".to_string(),
                 hour: "13:20".to_string(),
                 text: "How are you?".to_string(),
+                ..Default::default()
             },
         ];
         let avg = average_messages_per_user(&messages[..]).unwrap();
         // Alice has 2, Bob has 1 → total 3 / 2 users = 1.5
         assert!((avg - 1.5).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn test_messages_per_month_and_weekday() {
+        let at = |y, m, d| NaiveDate::from_ymd_opt(y, m, d)?.and_hms_opt(12, 0, 0);
+        let messages = [
+            Message {
+                owner: "Alice".to_string(),
+                timestamp: at(2022, 12, 31),
+                ..Default::default()
+            },
+            Message {
+                owner: "Bob".to_string(),
+                timestamp: at(2022, 12, 24),
+                ..Default::default()
+            },
+            Message {
+                owner: "Alice".to_string(),
+                timestamp: at(2023, 1, 1),
+                ..Default::default()
+            },
+            Message {
+                owner: "Bob".to_string(),
+                ..Default::default()
+            },
+        ];
+        let months = messages_per_month(&messages[..]).unwrap();
+        assert_eq!(months.get("2022-12"), Some(&2));
+        assert_eq!(months.get("2023-01"), Some(&1));
+        let weekdays = messages_per_weekday(&messages[..]).unwrap();
+        assert_eq!(weekdays.get("Saturday"), Some(&2));
+        assert_eq!(weekdays.get("Sunday"), Some(&1));
+    }
+
+    #[test]
+    fn test_stats_skip_system_messages() {
+        let messages = [
+            Message {
+                owner: "Alice".to_string(),
+                text: "Hello there".to_string(),
+                ..Default::default()
+            },
+            Message {
+                owner: "Alice".to_string(),
+                text: "Alice added Bob".to_string(),
+                kind: MessageKind::System(SystemEvent::MemberAdded {
+                    actor: "Alice".to_string(),
+                    members: vec!["Bob".to_string()],
+                }),
+                ..Default::default()
+            },
+        ];
+        let counts = messages_per_user(&messages[..]).unwrap();
+        assert_eq!(counts.get("Alice"), Some(&1));
+        assert_eq!(words_sent(&messages[..]).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_media_per_user() {
+        let image = || Attachment {
+            kind: AttachmentKind::Image,
+            file_name: None,
+        };
+        let messages = [
+            Message {
+                owner: "Alice".to_string(),
+                attachment: Some(image()),
+                ..Default::default()
+            },
+            Message {
+                owner: "Alice".to_string(),
+                text: "look".to_string(),
+                attachment: Some(image()),
+                ..Default::default()
+            },
+            Message {
+                owner: "Bob".to_string(),
+                text: "nice".to_string(),
+                ..Default::default()
+            },
+        ];
+        let media = media_per_user(&messages[..]).unwrap();
+        assert_eq!(media["Alice"].get("image"), Some(&2));
+        assert!(!media.contains_key("Bob"));
+        assert_eq!(words_sent(&messages[..]).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_edits_per_user() {
+        let messages = [
+            Message {
+                owner: "Alice".to_string(),
+                deleted: true,
+                ..Default::default()
+            },
+            Message {
+                owner: "Alice".to_string(),
+                text: "fixed typo".to_string(),
+                edited: true,
+                ..Default::default()
+            },
+            Message {
+                owner: "Bob".to_string(),
+                text: "hi".to_string(),
+                ..Default::default()
+            },
+        ];
+        let edits = edits_per_user(&messages[..]).unwrap();
+        assert_eq!(edits["Alice"].deleted, 1);
+        assert_eq!(edits["Alice"].edited, 1);
+        assert!(!edits.contains_key("Bob"));
+    }
 }
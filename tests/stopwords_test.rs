@@ -0,0 +1,66 @@
+use std::path::Path;
+use whatsapp_stats::parser::Parser;
+
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{
+        source::ChatSource, stats::total_word_count, stopwords::Stopwords, tokenizer::Tokenizer,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_parse_stopwords() {
+        let stopwords = Stopwords::parse("# comment\nthe\n\n  Don't \n");
+        assert!(stopwords.contains("the"));
+        assert!(stopwords.contains("THE"));
+        assert!(stopwords.contains("don\u{2019}t"));
+        assert!(!stopwords.contains("# comment"));
+        assert!(!stopwords.contains(""));
+    }
+
+    #[test]
+    fn test_language_stopwords() {
+        let spanish = Stopwords::language("ES").unwrap();
+        assert!(spanish.contains("que"));
+        assert!(spanish.contains("Qué"));
+        assert!(!spanish.contains("playa"));
+        assert!(Stopwords::language("tlh").is_none());
+        assert!(Stopwords::languages().all(|code| Stopwords::language(code).is_some()));
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let parser = Parser::new(Path::new("./tests/mocks/spanish_messages.txt"));
+        let messages = parser.parse().unwrap();
+        assert_eq!(Stopwords::detect_language(&messages), Some("es"));
+
+        let parser = Parser::new(Path::new("./tests/mocks/android_messages.txt"));
+        let messages = parser.parse().unwrap();
+        assert_eq!(Stopwords::detect_language(&messages), Some("en"));
+    }
+
+    #[test]
+    fn test_total_word_count_without_stopwords() {
+        let parser = Parser::new(Path::new("./tests/mocks/spanish_messages.txt"));
+        let messages = parser.parse().unwrap();
+
+        let tokenizer = Tokenizer::new().with_stopwords(Stopwords::language("es").unwrap());
+        let counts = total_word_count(&messages, &tokenizer).unwrap();
+        assert_eq!(counts.get("playa"), Some(&3));
+        assert_eq!(counts.get("envidia"), Some(&1));
+        assert_eq!(counts.get("la"), None);
+        assert_eq!(counts.get("qué"), None);
+
+        let mut stopwords = Stopwords::from_file(Path::new("./tests/mocks/stopwords.txt")).unwrap();
+        stopwords.extend(Stopwords::language("es").unwrap());
+        let tokenizer = Tokenizer::new().with_stopwords(stopwords);
+        let counts = total_word_count(&messages, &tokenizer).unwrap();
+        assert_eq!(counts.get("playa"), None);
+        assert_eq!(counts.get("perros"), None);
+        assert_eq!(counts.get("envidia"), Some(&1));
+
+        let counts = total_word_count(&messages, &Tokenizer::new()).unwrap();
+        assert_eq!(counts.get("la"), Some(&3));
+    }
+}
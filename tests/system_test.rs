@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::system::SystemEvent;
+
+    #[test]
+    fn test_parse_system_events() {
+        assert_eq!(
+            SystemEvent::parse(
+                "Messages and calls are end-to-end encrypted. No one outside of this chat can read them."
+            ),
+            Some(SystemEvent::EncryptionNotice)
+        );
+        assert_eq!(
+            SystemEvent::parse("Alice changed the subject from \"Trip\" to \"Trip 2023\""),
+            Some(SystemEvent::SubjectChanged {
+                actor: "Alice".to_string(),
+                subject: "Trip 2023".to_string(),
+            })
+        );
+        assert_eq!(
+            SystemEvent::parse("Bob joined using this group's invite link"),
+            Some(SystemEvent::MemberJoined {
+                member: "Bob".to_string()
+            })
+        );
+        assert_eq!(
+            SystemEvent::parse("Your security code with Carol changed. Tap to learn more."),
+            Some(SystemEvent::SecurityCodeChanged {
+                member: "Carol".to_string()
+            })
+        );
+        assert_eq!(
+            SystemEvent::parse("You changed this group's icon"),
+            Some(SystemEvent::IconChanged {
+                actor: "You".to_string()
+            })
+        );
+        assert_eq!(SystemEvent::parse("See you tomorrow"), None);
+    }
+}
@@ -0,0 +1,88 @@
+use std::path::Path;
+use whatsapp_stats::importers::telegram::TelegramImporter;
+
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{
+        attachment::{Attachment, AttachmentKind},
+        diagnostics::RejectReason,
+        errors::ParseError,
+        message::{Message, MessageKind},
+        source::ChatSource,
+        system::SystemEvent,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_import_telegram() {
+        let importer = TelegramImporter::new(Path::new("./tests/mocks/telegram_result.json"));
+
+        let messages: Vec<Message> = importer.parse().unwrap();
+        assert_eq!(messages.len(), 6);
+        assert_eq!(
+            messages[0].kind,
+            MessageKind::System(SystemEvent::GroupCreated {
+                actor: "Alice".to_string(),
+                subject: "Trip".to_string(),
+            })
+        );
+        assert_eq!(messages[1].owner, "Alice");
+        assert_eq!(messages[1].text, "Hello");
+        assert_eq!(
+            messages[1].timestamp.map(|t| t.to_string()),
+            Some("2022-05-13 20:37:05".to_string())
+        );
+        assert_eq!(messages[2].text, "Look at https://example.com now");
+        assert!(messages[2].edited);
+        assert_eq!(
+            messages[5].kind,
+            MessageKind::System(SystemEvent::MemberLeft {
+                member: "Bob".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_import_telegram_media() {
+        let importer = TelegramImporter::new(Path::new("./tests/mocks/telegram_result.json"));
+
+        let messages: Vec<Message> = importer.parse().unwrap();
+        assert_eq!(
+            messages[3].attachment,
+            Some(Attachment {
+                kind: AttachmentKind::Image,
+                file_name: Some("photo_1@13-05-2022_20-40-00.jpg".to_string()),
+            })
+        );
+        assert_eq!(messages[3].text, "Nice view");
+        assert_eq!(
+            messages[4].attachment,
+            Some(Attachment {
+                kind: AttachmentKind::Audio,
+                file_name: None,
+            })
+        );
+        assert_eq!(messages[4].owner, "Deleted Account");
+    }
+
+    #[test]
+    fn test_import_telegram_skips_unreadable_dates() {
+        let importer = TelegramImporter::new(Path::new("./tests/mocks/telegram_bad_date.json"));
+        let (messages, report) = importer.parse_with_report().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(report.lines_read, 2);
+        assert_eq!(report.rejects[0].line_number, 2);
+        assert_eq!(report.rejects[0].reason, RejectReason::InvalidTimestamp);
+
+        let importer =
+            TelegramImporter::new(Path::new("./tests/mocks/telegram_bad_date.json")).strict();
+        assert!(matches!(
+            importer.parse(),
+            Err(ParseError::InvalidEntryTimestamp {
+                entry_number: 2,
+                ..
+            })
+        ));
+    }
+}
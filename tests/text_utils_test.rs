@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::utils::text_utils::{blank_spans, normalize_line, normalize_name};
+
+    #[test]
+    fn test_normalize_line() {
+        assert_eq!(
+            normalize_line("\u{FEFF}\u{200E}[1/1/22, 9:00\u{202F}AM] Bob: hi"),
+            "[1/1/22, 9:00 AM] Bob: hi"
+        );
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(
+            normalize_name(" \u{2068}Alice Smith\u{2069}\u{200E} "),
+            "Alice Smith"
+        );
+        assert_eq!(normalize_name("~\u{00A0}Bob"), "~ Bob");
+    }
+
+    #[test]
+    fn test_blank_spans() {
+        assert_eq!(blank_spans("hi@Bob!bye", std::iter::once(2..6)), "hi !bye");
+        assert_eq!(blank_spans("a😂b😂", [1..5, 6..10]), "a b ");
+        assert_eq!(blank_spans("plain", []), "plain");
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use whatsapp_stats::{
+        errors::ParseError,
+        utils::time_utils::{DateOrder, DateOrderDetector, normalize_time, parse_timestamp},
+    };
+
+    #[test]
+    fn test_normalize_24_hour_time() {
+        assert_eq!(normalize_time("20:37"), Some("20:37".to_string()));
+        assert_eq!(normalize_time("9:05:12"), Some("09:05:12".to_string()));
+        assert_eq!(normalize_time("24:00"), None);
+    }
+
+    #[test]
+    fn test_normalize_12_hour_time() {
+        assert_eq!(normalize_time("9:41 PM"), Some("21:41".to_string()));
+        assert_eq!(normalize_time("9:41\u{202F}AM"), Some("09:41".to_string()));
+        assert_eq!(normalize_time("12:05:30 AM"), Some("00:05:30".to_string()));
+        assert_eq!(normalize_time("12:05 p. m."), Some("12:05".to_string()));
+        assert_eq!(normalize_time("오후 3:10"), Some("15:10".to_string()));
+        assert_eq!(normalize_time("13:00 PM"), None);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected = NaiveDate::from_ymd_opt(2022, 12, 31)
+            .unwrap()
+            .and_hms_opt(21, 41, 5)
+            .unwrap();
+        let dmy = DateOrder::DayMonthYear;
+        assert_eq!(parse_timestamp("31/12/22", "21:41:05", dmy), Some(expected));
+        assert_eq!(
+            parse_timestamp("31.12.2022", "21:41:05", dmy),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_timestamp("2022-12-31", "21:41:05", dmy),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_timestamp("12/31/22", "21:41:05", DateOrder::MonthDayYear),
+            Some(expected)
+        );
+        assert_eq!(parse_timestamp("31/13/22", "21:41", dmy), None);
+    }
+
+    #[test]
+    fn test_detect_date_order() {
+        let detect = |dates: &[&str]| {
+            let mut detector = DateOrderDetector::default();
+            for date in dates {
+                detector.observe(date);
+            }
+            detector.finish()
+        };
+        assert!(matches!(
+            detect(&["3/5/22", "20/5/22"]),
+            Ok(DateOrder::DayMonthYear)
+        ));
+        assert!(matches!(
+            detect(&["3/5/22", "5/20/22"]),
+            Ok(DateOrder::MonthDayYear)
+        ));
+        assert!(matches!(
+            detect(&["2022-05-03"]),
+            Ok(DateOrder::YearMonthDay)
+        ));
+        assert!(matches!(
+            detect(&["3/5/22", "4/5/22"]),
+            Err(ParseError::AmbiguousDateOrder)
+        ));
+        assert!(matches!(
+            detect(&["20/5/22", "5/20/22"]),
+            Err(ParseError::ConflictingDateOrder)
+        ));
+    }
+
+    #[test]
+    fn test_ambiguous_date_order_names_the_flag() {
+        let message = ParseError::AmbiguousDateOrder.to_string();
+        assert!(message.contains("--date-format dmy"));
+        assert!(message.contains("--date-format mdy"));
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use whatsapp_stats::{
+        message::Message,
+        stats::{count_word_per_user, extract_word_count, total_word_count, words_sent},
+        tokenizer::Tokenizer,
+    };
+
+    #[test]
+    fn test_tokens() {
+        let tokenizer = Tokenizer::new();
+        assert_eq!(
+            tokenizer.tokens("Hello, hello! HELLO... don't stop"),
+            vec!["hello", "hello", "hello", "don't", "stop"]
+        );
+        assert_eq!(tokenizer.tokens("ÉCOLE Straße"), vec!["école", "straße"]);
+        assert!(tokenizer.tokens("?! -- ...").is_empty());
+    }
+
+    #[test]
+    fn test_keep_case_and_fold_accents() {
+        assert_eq!(
+            Tokenizer::new().keep_case().tokens("Hello World"),
+            vec!["Hello", "World"]
+        );
+        assert_eq!(
+            Tokenizer::new().fold_accents().tokens("Café crème, ÇA VA"),
+            vec!["cafe", "creme", "ca", "va"]
+        );
+    }
+
+    #[test]
+    fn test_phrases() {
+        assert_eq!(
+            Tokenizer::new().phrases("Good morning! See you, Bob's friend 🙂 soon"),
+            vec![
+                vec!["good", "morning"],
+                vec!["see", "you"],
+                vec!["bob's", "friend"],
+                vec!["soon"],
+            ]
+        );
+        assert!(Tokenizer::new().phrases("...").is_empty());
+    }
+
+    #[test]
+    fn test_word_stats_share_tokenizer() {
+        let message_1 = Message {
+            owner: "Alice".to_string(),
+            date: "3/5/22".to_string(),
+            hour: "20:37".to_string(),
+            text: "Café? CAFÉ! café, cafe".to_string(),
+            ..Default::default()
+        };
+        let message_2 = Message {
+            owner: "Bob".to_string(),
+            date: "3/5/22".to_string(),
+            hour: "20:38".to_string(),
+            text: "no coffee".to_string(),
+            ..Default::default()
+        };
+        let messages_array = [message_1, message_2];
+        let tokenizer = Tokenizer::new();
+        let counts = total_word_count(&messages_array[..], &tokenizer).unwrap();
+        assert_eq!(counts.get("café"), Some(&3));
+        assert_eq!(counts.get("cafe"), Some(&1));
+        assert_eq!(
+            extract_word_count(&messages_array[..], "Café", &tokenizer).unwrap(),
+            3
+        );
+        let users = count_word_per_user(&messages_array[..], "CAFÉ!", &tokenizer).unwrap();
+        assert_eq!(users.get("Alice"), Some(&1));
+        assert_eq!(users.get("Bob"), None);
+
+        let folded = Tokenizer::new().fold_accents();
+        assert_eq!(
+            extract_word_count(&messages_array[..], "cafe", &folded).unwrap(),
+            4
+        );
+        assert_eq!(words_sent(&messages_array[..]).unwrap(), 6);
+    }
+}